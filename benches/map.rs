@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use context_iterators::*;
+
+const LEN: u64 = 10_000;
+
+fn map(c: &mut Criterion) {
+    let data: Vec<u64> = (0..LEN).collect();
+    let mut group = c.benchmark_group("map");
+
+    group.bench_function("loop", |b| {
+        b.iter(|| {
+            let offset = black_box(42);
+            for item in data.iter().copied() {
+                black_box(item + offset);
+            }
+        })
+    });
+
+    group.bench_function("map", |b| {
+        b.iter(|| {
+            let offset = black_box(42);
+            data.iter()
+                .copied()
+                .map(|item| item + offset)
+                .for_each(|item| {
+                    black_box(item);
+                })
+        })
+    });
+
+    group.bench_function("map_with_context", |b| {
+        b.iter(|| {
+            data.iter()
+                .copied()
+                .with_context(black_box(42))
+                .map_with_context(|item: u64, offset: &u64| item + offset)
+                .for_each(|item| {
+                    black_box(item);
+                })
+        })
+    });
+
+    group.bench_function("context_map", |b| {
+        b.iter(|| {
+            data.iter()
+                .copied()
+                .with_context((0, black_box(42)))
+                .context_map(|context: &(u64, u64)| &context.1)
+                .map_with_context(|item: u64, offset: &u64| item + offset)
+                .for_each(|item| {
+                    black_box(item);
+                })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, map);
+criterion_main!(benches);
@@ -0,0 +1,202 @@
+//! Adaptors yielding a running accumulation of the elements.
+
+use core::iter::FusedIterator;
+use core::ops::{Add, Mul};
+
+use crate::ContextIterator;
+
+/// Yield the running sum of the elements of an iterator, scaled by the
+/// context.
+///
+/// Created by [`ContextIterator::prefix_sum_with_context`]. The context is
+/// read again for each element.
+#[derive(Clone, Debug)]
+pub struct PrefixSumCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) sum: Option<I::Item>,
+}
+
+impl<I> Iterator for PrefixSumCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Add<Output = I::Item> + Mul<I::Context, Output = I::Item> + Clone,
+    I::Context: Copy,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()? * *self.iter.context();
+        let sum = match self.sum.take() {
+            Some(sum) => sum + item,
+            None => item,
+        };
+        self.sum = Some(sum.clone());
+        Some(sum)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for PrefixSumCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Item: Add<Output = I::Item> + Mul<I::Context, Output = I::Item> + Clone,
+    I::Context: Copy,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for PrefixSumCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: Add<Output = I::Item> + Mul<I::Context, Output = I::Item> + Clone,
+    I::Context: Copy,
+{
+}
+
+impl<I> ContextIterator for PrefixSumCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Add<Output = I::Item> + Mul<I::Context, Output = I::Item> + Clone,
+    I::Context: Copy,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield the accumulator of a left fold after each element of an iterator.
+///
+/// Created by [`ContextIterator::accumulate_with_context`]. The initial value
+/// of the accumulator is not yielded.
+#[derive(Clone, Debug)]
+pub struct AccumulateCtx<I, A>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) acc: Option<A>,
+    pub(crate) f: fn(A, I::Item, &I::Context) -> A,
+}
+
+impl<I, A> Iterator for AccumulateCtx<I, A>
+where
+    I: ContextIterator,
+    A: Clone,
+{
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let acc = (self.f)(self.acc.take()?, item, self.iter.context());
+        self.acc = Some(acc.clone());
+        Some(acc)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, A> ExactSizeIterator for AccumulateCtx<I, A>
+where
+    I: ExactSizeIterator + ContextIterator,
+    A: Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, A> FusedIterator for AccumulateCtx<I, A>
+where
+    I: FusedIterator + ContextIterator,
+    A: Clone,
+{
+}
+
+impl<I, A> ContextIterator for AccumulateCtx<I, A>
+where
+    I: ContextIterator,
+    A: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn prefix_sum() {
+        let iter = (1..5).with_context(2).prefix_sum_with_context();
+        assert_eq!(iter.context(), &2);
+        assert!(iter.eq([2, 6, 12, 20]));
+
+        let mut iter = core::iter::once(3)
+            .with_context(2)
+            .prefix_sum_with_context();
+        assert_eq!(iter.next(), Some(6));
+        assert_eq!(iter.next(), None);
+
+        let mut iter = empty_with_context::<u32, _>(2).prefix_sum_with_context();
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn accumulate() {
+        let iter = [3, 1, 7, 2, 9]
+            .into_iter()
+            .with_context(8)
+            .accumulate_with_context(0, |max: u32, item: u32, threshold: &u32| {
+                if item < *threshold {
+                    max.max(item)
+                } else {
+                    max
+                }
+            });
+
+        assert_eq!(iter.context(), &8);
+        assert_eq!(iter.len(), 5);
+        assert!(iter.eq([3, 3, 7, 7, 7]));
+    }
+}
@@ -0,0 +1,122 @@
+//! Adaptors turning a context iterator into a self-describing stream.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// An element of an [`AnnotateCtx`] stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Annotated<T, Ctx> {
+    /// An element of the original iterator.
+    Item(T),
+    /// The context the following elements are yielded under.
+    Context(Ctx),
+}
+
+/// Interleave the elements of an iterator with snapshots of its context.
+///
+/// Created by [`ContextIterator::annotate_with_context`]. A
+/// [`Annotated::Context`] is yielded before the first element, and again
+/// before any element yielded under a different context.
+#[derive(Clone, Debug)]
+pub struct AnnotateCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) last: Option<I::Context>,
+    pub(crate) pending: Option<I::Item>,
+}
+
+impl<I> Iterator for AnnotateCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Item = Annotated<I::Item, I::Context>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(Annotated::Item(item));
+        }
+        let item = self.iter.next()?;
+        let context = self.iter.context();
+        if self.last.as_ref() == Some(context) {
+            return Some(Annotated::Item(item));
+        }
+        self.last = Some(context.clone());
+        self.pending = Some(item);
+        Some(Annotated::Context(context.clone()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let pending = self.pending.is_some() as usize;
+        (
+            lower.saturating_add(pending),
+            upper.and_then(|upper| upper.checked_mul(2)?.checked_add(pending)),
+        )
+    }
+}
+
+impl<I> FusedIterator for AnnotateCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+}
+
+impl<I> ContextIterator for AnnotateCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn annotate() {
+        let iter = (0..2).with_context('a').annotate_with_context();
+
+        assert_eq!(iter.context(), &'a');
+        assert!(iter.eq([
+            Annotated::Context('a'),
+            Annotated::Item(0),
+            Annotated::Item(1),
+        ]));
+
+        let iter = (0..4)
+            .with_context(0)
+            .update_context_each(|context: &mut usize| *context += 1)
+            .filter_with_context(|item: &usize, _| item % 2 == 0)
+            .annotate_with_context();
+
+        assert!(iter.eq([
+            Annotated::Context(0),
+            Annotated::Item(0),
+            Annotated::Context(2),
+            Annotated::Item(2),
+        ]));
+    }
+}
@@ -0,0 +1,102 @@
+//! Type-erased context iterators.
+
+use alloc::boxed::Box;
+
+use crate::ContextIterator;
+
+/// Context iterator that can be cloned behind a trait object.
+///
+/// This trait is automatically implemented for all [`ContextIterator`]s that
+/// implement [`Clone`]. Boxed trait objects of it implement [`Clone`] too.
+///
+/// ```
+/// use context_iterators::*;
+///
+/// type BoxedIter = Box<dyn CloneableContextIterator<Item = usize, Context = usize>>;
+///
+/// let iter: BoxedIter = Box::new(
+///     (0..3)
+///         .with_context(10)
+///         .map_with_context(|item: usize, context: &usize| item + context),
+/// );
+/// let copy = iter.clone();
+///
+/// assert_eq!(copy.context(), &10);
+/// assert!(iter.eq(copy));
+/// ```
+pub trait CloneableContextIterator: ContextIterator {
+    /// Clone the iterator into a new box.
+    fn clone_box<'a>(
+        &self,
+    ) -> Box<dyn CloneableContextIterator<Item = Self::Item, Context = Self::Context> + 'a>
+    where
+        Self: 'a;
+}
+
+impl<I> CloneableContextIterator for I
+where
+    I: ContextIterator + Clone,
+{
+    fn clone_box<'a>(
+        &self,
+    ) -> Box<dyn CloneableContextIterator<Item = Self::Item, Context = Self::Context> + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self.clone())
+    }
+}
+
+impl<'a, T, Ctx> Clone for Box<dyn CloneableContextIterator<Item = T, Context = Ctx> + 'a>
+where
+    T: 'a,
+    Ctx: 'a,
+{
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+impl<I> ContextIterator for Box<I>
+where
+    I: ContextIterator + ?Sized,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        (**self).context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        (**self).context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        (**self).context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::boxed::Box;
+
+    use crate::*;
+
+    #[test]
+    fn clone_box() {
+        let iter: MapWithCtx<_, usize, usize> = (0..3)
+            .with_context(10)
+            .map_with_context(|item: usize, context: &usize| item * context);
+        let boxed: Box<dyn CloneableContextIterator<Item = usize, Context = usize>> =
+            Box::new(iter);
+
+        let mut copy = boxed.clone_box();
+        assert_eq!(copy.next(), Some(0));
+        assert_eq!(copy.context(), &10);
+        assert!(boxed.eq([0, 10, 20]));
+        assert!(copy.eq([10, 20]));
+    }
+}
@@ -0,0 +1,429 @@
+//! Adaptors that insert additional elements into the iterator.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Yield an element built from the context before the elements of an
+/// iterator.
+///
+/// Created by [`ContextIterator::prepend_context`].
+#[derive(Clone, Debug)]
+pub struct PrependCtxCtx<I> {
+    pub(crate) iter: I,
+    pub(crate) pending: bool,
+}
+
+impl<I> Iterator for PrependCtxCtx<I>
+where
+    I: ContextIterator,
+    I::Item: From<I::Context>,
+    I::Context: Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending {
+            self.pending = false;
+            return Some(self.iter.context().clone().into());
+        }
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let pending = self.pending as usize;
+        (
+            lower.saturating_add(pending),
+            upper.and_then(|upper| upper.checked_add(pending)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for PrependCtxCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Item: From<I::Context>,
+    I::Context: Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len() + self.pending as usize
+    }
+}
+
+impl<I> FusedIterator for PrependCtxCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: From<I::Context>,
+    I::Context: Clone,
+{
+}
+
+impl<I> ContextIterator for PrependCtxCtx<I>
+where
+    I: ContextIterator,
+    I::Item: From<I::Context>,
+    I::Context: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Insert a separator between consecutive elements that map to different
+/// keys.
+///
+/// Created by [`ContextIterator::group_separators_with_context`]. The
+/// separator is built from the keys of the two adjacent elements and the
+/// context. One element is buffered while its separator is yielded.
+#[derive(Clone, Debug)]
+pub struct GroupSepCtx<I, K>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) key: fn(&I::Item, &I::Context) -> K,
+    pub(crate) sep: fn(&K, &K, &I::Context) -> I::Item,
+    pub(crate) last: Option<K>,
+    pub(crate) pending: Option<I::Item>,
+}
+
+impl<I, K> Iterator for GroupSepCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(item);
+        }
+        let item = self.iter.next()?;
+        let context = self.iter.context();
+        let key = (self.key)(&item, context);
+        let sep = match &self.last {
+            Some(last) if *last != key => (self.sep)(last, &key, context),
+            _ => {
+                self.last = Some(key);
+                return Some(item);
+            }
+        };
+        self.last = Some(key);
+        self.pending = Some(item);
+        Some(sep)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let pending = self.pending.is_some() as usize;
+        (
+            lower.saturating_add(pending),
+            upper.and_then(|upper| upper.checked_mul(2)?.checked_add(pending)),
+        )
+    }
+}
+
+impl<I, K> FusedIterator for GroupSepCtx<I, K>
+where
+    I: FusedIterator + ContextIterator,
+    K: PartialEq,
+{
+}
+
+impl<I, K> ContextIterator for GroupSepCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield the elements of an iterator followed by a trailer built from an
+/// accumulator.
+///
+/// Created by [`ContextIterator::with_trailer`]. The elements are passed
+/// through unchanged, and the trailer is yielded exactly once, after the
+/// last element.
+#[derive(Clone, Debug)]
+pub struct TrailerCtx<I, A>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) acc: Option<A>,
+    pub(crate) step: fn(A, &I::Item, &I::Context) -> A,
+    pub(crate) trailer: fn(A, &I::Context) -> I::Item,
+}
+
+impl<I, A> Iterator for TrailerCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let acc = self.acc.take()?;
+        match self.iter.next() {
+            Some(item) => {
+                self.acc = Some((self.step)(acc, &item, self.iter.context()));
+                Some(item)
+            }
+            None => Some((self.trailer)(acc, self.iter.context())),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.acc.is_none() {
+            return (0, Some(0));
+        }
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(1),
+            upper.and_then(|upper| upper.checked_add(1)),
+        )
+    }
+}
+
+impl<I, A> ExactSizeIterator for TrailerCtx<I, A> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I, A> FusedIterator for TrailerCtx<I, A> where I: ContextIterator {}
+
+impl<I, A> ContextIterator for TrailerCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Insert interpolated elements between each pair of consecutive elements.
+///
+/// Created by [`ContextIterator::interpolate_with_context`]. The last yielded
+/// element of the iterator is buffered, along with the next one while the
+/// elements between them are produced.
+#[derive(Clone, Debug)]
+pub struct InterpolateCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) factor: usize,
+    #[allow(clippy::type_complexity)]
+    pub(crate) lerp: fn(&I::Item, &I::Item, f64, &I::Context) -> I::Item,
+    pub(crate) last: Option<I::Item>,
+    pub(crate) next: Option<I::Item>,
+    /// The index of the next interpolated element between `last` and `next`.
+    pub(crate) step: usize,
+}
+
+impl<I> InterpolateCtx<I>
+where
+    I: ContextIterator,
+{
+    /// The number of elements left to yield, given the number of elements
+    /// left in the inner iterator.
+    fn remaining(&self, inner: usize) -> Option<usize> {
+        let pending = match (&self.last, &self.next) {
+            (None, _) if inner == 0 => return Some(0),
+            (None, _) => return (inner - 1).checked_mul(self.factor)?.checked_add(1),
+            (Some(_), None) => 0,
+            (Some(_), Some(_)) => self.factor - self.step + 1,
+        };
+        inner.checked_mul(self.factor)?.checked_add(pending)
+    }
+}
+
+impl<I> Iterator for InterpolateCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(last) = &self.last else {
+            let item = self.iter.next()?;
+            self.last = Some(item.clone());
+            return Some(item);
+        };
+        if self.next.is_none() {
+            self.next = Some(self.iter.next()?);
+            self.step = 1;
+        }
+        let next = self.next.as_ref().unwrap();
+        if self.step < self.factor {
+            let t = self.step as f64 / self.factor as f64;
+            self.step += 1;
+            return Some((self.lerp)(last, next, t, self.iter.context()));
+        }
+        let next = self.next.take().unwrap();
+        self.last = Some(next.clone());
+        Some(next)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (
+            self.remaining(lower).unwrap_or(usize::MAX),
+            upper.and_then(|upper| self.remaining(upper)),
+        )
+    }
+}
+
+impl<I> FusedIterator for InterpolateCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: Clone,
+{
+}
+
+impl<I> ContextIterator for InterpolateCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn prepend_context() {
+        let iter = (1..4).with_context(42).prepend_context();
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.size_hint().1, Some(4));
+        assert!(iter.eq([42, 1, 2, 3]));
+
+        let iter = (1u64..3).with_context(7u32).prepend_context();
+        assert!(iter.eq([7, 1, 2]));
+    }
+
+    #[test]
+    fn group_separators() {
+        let iter = ["apple", "avocado", "banana", "cherry", "cranberry"]
+            .into_iter()
+            .with_context("--")
+            .group_separators_with_context(
+                |item: &&str, _| item.as_bytes()[0],
+                |_, _, separator: &&str| separator,
+            );
+
+        assert_eq!(iter.context(), &"--");
+        assert!(iter.eq([
+            "apple",
+            "avocado",
+            "--",
+            "banana",
+            "--",
+            "cherry",
+            "cranberry"
+        ]));
+    }
+
+    #[test]
+    fn trailer() {
+        let mut iter = (1..4).with_context(100).with_trailer(
+            0,
+            |sum, item: &u32, _| sum + item,
+            |sum, base: &u32| base + sum,
+        );
+
+        assert_eq!(iter.context(), &100);
+        assert_eq!(iter.len(), 4);
+        assert!(iter.by_ref().eq([1, 2, 3, 106]));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn interpolate() {
+        fn lerp(a: &i32, b: &i32, t: f64, _: &usize) -> i32 {
+            a + ((b - a) as f64 * t) as i32
+        }
+
+        let mut iter = [0, 30, 0]
+            .into_iter()
+            .with_context(3)
+            .interpolate_with_context(|factor: &usize| *factor, lerp);
+
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.size_hint(), (7, Some(7)));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+        assert!(iter.eq([20, 30, 20, 10, 0]));
+
+        let iter = [5].into_iter().with_context(3);
+        assert!(iter.interpolate_with_context(|f| *f, lerp).eq([5]));
+        let iter = [].into_iter().with_context(3);
+        assert_eq!(iter.interpolate_with_context(|f| *f, lerp).next(), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! Adaptors grouping consecutive elements together.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use crate::{ContextIterator, FinalizableContextIterator};
+
+/// Group the elements of an iterator into chunks with a size read from the
+/// context.
+///
+/// Created by [`ContextIterator::chunks_from_context`]. The chunk size is read
+/// again at the start of each chunk, so an evolving context can change it
+/// mid-stream. The last chunk may be shorter.
+#[derive(Clone, Debug)]
+pub struct ChunksFromCtxCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) size: fn(&I::Context) -> usize,
+}
+
+impl<I> Iterator for ChunksFromCtxCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = Vec<I::Item>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = (self.size)(self.iter.context());
+        assert!(
+            size != 0,
+            "chunks_from_context: chunk size must be non-zero"
+        );
+        let first = self.iter.next()?;
+        let mut chunk = Vec::with_capacity(size);
+        chunk.push(first);
+        chunk.extend(self.iter.by_ref().take(size - 1));
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I> FusedIterator for ChunksFromCtxCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for ChunksFromCtxCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Group the elements of an iterator into fixed-size arrays.
+///
+/// Created by [`ContextIterator::array_chunks_with_context`]. A trailing
+/// partial chunk is not yielded, but kept until [`ArrayChunksCtx::into_remainder`]
+/// is called.
+#[derive(Clone, Debug)]
+pub struct ArrayChunksCtx<I, const N: usize>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) remainder: Vec<I::Item>,
+}
+
+impl<I, const N: usize> ArrayChunksCtx<I, N>
+where
+    I: ContextIterator,
+{
+    /// Return the elements of the trailing partial chunk.
+    ///
+    /// This is empty until the iterator has been exhausted, or if the number
+    /// of elements was a multiple of `N`.
+    pub fn into_remainder(self) -> Vec<I::Item> {
+        self.remainder
+    }
+}
+
+impl<I, const N: usize> Iterator for ArrayChunksCtx<I, N>
+where
+    I: ContextIterator,
+    I::Item: Copy,
+{
+    type Item = [I::Item; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.iter.next()?;
+        let mut chunk = [first; N];
+        for i in 1..N {
+            match self.iter.next() {
+                Some(item) => chunk[i] = item,
+                None => {
+                    self.remainder.extend_from_slice(&chunk[..i]);
+                    return None;
+                }
+            }
+        }
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower / N, upper.map(|upper| upper / N))
+    }
+}
+
+impl<I, const N: usize> ExactSizeIterator for ArrayChunksCtx<I, N>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Item: Copy,
+{
+}
+
+impl<I, const N: usize> FusedIterator for ArrayChunksCtx<I, N>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: Copy,
+{
+}
+
+impl<I, const N: usize> ContextIterator for ArrayChunksCtx<I, N>
+where
+    I: ContextIterator,
+    I::Item: Copy,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Group the elements of an iterator into chunks and reduce each one.
+///
+/// Created by [`ContextIterator::chunk_reduce_with_context`]. The last chunk
+/// may be shorter.
+#[derive(Clone, Debug)]
+pub struct ChunkReduceCtx<I, A>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) size: usize,
+    pub(crate) reduce: fn(Vec<I::Item>, &I::Context) -> A,
+}
+
+impl<I, A> Iterator for ChunkReduceCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.iter.next()?;
+        let mut chunk = Vec::with_capacity(self.size);
+        chunk.push(first);
+        chunk.extend(self.iter.by_ref().take(self.size - 1));
+        Some((self.reduce)(chunk, self.iter.context()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = |n: usize| n / self.size + (n % self.size != 0) as usize;
+        let (lower, upper) = self.iter.size_hint();
+        (chunks(lower), upper.map(chunks))
+    }
+}
+
+impl<I, A> FusedIterator for ChunkReduceCtx<I, A> where I: FusedIterator + ContextIterator {}
+
+impl<I, A> ContextIterator for ChunkReduceCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Apply a function to each window of consecutive elements of an iterator.
+///
+/// Created by [`ContextIterator::rolling_with_context`]. The windows overlap,
+/// and only full windows are passed to the function.
+#[derive(Clone, Debug)]
+pub struct RollingCtx<I, A>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) window: usize,
+    pub(crate) map: fn(&[I::Item], &I::Context) -> A,
+    pub(crate) buffer: VecDeque<I::Item>,
+}
+
+impl<I, A> Iterator for RollingCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.len() == self.window {
+            let item = self.iter.next()?;
+            self.buffer.pop_front();
+            self.buffer.push_back(item);
+        } else {
+            while self.buffer.len() < self.window {
+                self.buffer.push_back(self.iter.next()?);
+            }
+        }
+        Some((self.map)(
+            self.buffer.make_contiguous(),
+            self.iter.context(),
+        ))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if self.buffer.len() == self.window {
+            return (lower, upper);
+        }
+        let missing = self.window - self.buffer.len() - 1;
+        (
+            lower.saturating_sub(missing),
+            upper.map(|upper| upper.saturating_sub(missing)),
+        )
+    }
+}
+
+impl<I, A> FusedIterator for RollingCtx<I, A> where I: FusedIterator + ContextIterator {}
+
+impl<I, A> FinalizableContextIterator for RollingCtx<I, A>
+where
+    I: ContextIterator,
+{
+    /// Apply the function to the partial window, if the iterator was shorter
+    /// than the window.
+    ///
+    /// Nothing is released if a full window was produced.
+    #[inline]
+    fn finalize(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() || self.buffer.len() == self.window {
+            return None;
+        }
+        let item = (self.map)(self.buffer.make_contiguous(), self.iter.context());
+        self.buffer.clear();
+        Some(item)
+    }
+}
+
+impl<I, A> ContextIterator for RollingCtx<I, A>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn chunks_from_context() {
+        let iter = (0..5)
+            .with_context(2)
+            .chunks_from_context(|context: &usize| *context);
+
+        assert_eq!(iter.context(), &2);
+        assert!(iter.eq([vec![0, 1], vec![2, 3], vec![4]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero")]
+    fn chunks_from_context_zero() {
+        (0..5)
+            .with_context(0)
+            .chunks_from_context(|context: &usize| *context)
+            .for_each(drop);
+    }
+
+    #[test]
+    fn chunk_reduce() {
+        let iter = (0..7)
+            .with_context(10)
+            .chunk_reduce_with_context(3, |chunk: Vec<usize>, context: &usize| {
+                chunk.iter().sum::<usize>() * context
+            });
+
+        assert_eq!(iter.context(), &10);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert!(iter.eq([30, 120, 60]));
+    }
+
+    #[test]
+    fn rolling() {
+        let iter = (1..6)
+            .with_context(3)
+            .rolling_with_context(3, |window: &[usize], context: &usize| {
+                window.iter().sum::<usize>() / context
+            });
+
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert!(iter.eq([2, 3, 4]));
+
+        let mut iter = (0..2)
+            .with_context(())
+            .rolling_with_context(3, |window: &[usize], _| window.len());
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn rolling_finalize() {
+        let iter = (0..2)
+            .with_context(())
+            .rolling_with_context(3, |window: &[usize], _| window.len())
+            .with_finalize();
+        assert!(iter.eq([2]));
+
+        let iter = (0..4)
+            .with_context(())
+            .rolling_with_context(3, |window: &[usize], _| window.len())
+            .with_finalize();
+        assert!(iter.eq([3, 3]));
+
+        let iter = (0..0)
+            .with_context(())
+            .rolling_with_context(3, |window: &[usize], _| window.len())
+            .with_finalize();
+        assert!(iter.eq([]));
+    }
+
+    #[test]
+    fn array_chunks() {
+        let mut iter = "abcdefgh"
+            .chars()
+            .with_context(2)
+            .array_chunks_with_context::<3>();
+
+        assert_eq!(iter.context(), &2);
+        assert_eq!(iter.size_hint(), (0, Some(2)));
+        assert_eq!(iter.next(), Some(['a', 'b', 'c']));
+        assert_eq!(iter.next(), Some(['d', 'e', 'f']));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_remainder(), ['g', 'h']);
+
+        let mut iter = (0..6).with_context(()).array_chunks_with_context::<2>();
+        assert_eq!(iter.len(), 3);
+        iter.by_ref().for_each(drop);
+        assert!(iter.into_remainder().is_empty());
+    }
+}
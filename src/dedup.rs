@@ -0,0 +1,362 @@
+//! Adaptors merging or removing consecutive elements.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Remove consecutive elements that map to the same key.
+///
+/// Each key is computed from an element and the context of the iterator. The
+/// first element of each run is yielded.
+#[derive(Clone, Debug)]
+pub struct DedupByKeyCtx<I, K>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) key: fn(&I::Item, &I::Context) -> K,
+    pub(crate) last: Option<K>,
+}
+
+impl<I, K> Iterator for DedupByKeyCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let key = (self.key)(&item, self.iter.context());
+            if self.last.as_ref() != Some(&key) {
+                self.last = Some(key);
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, K> FusedIterator for DedupByKeyCtx<I, K>
+where
+    I: FusedIterator + ContextIterator,
+    K: PartialEq,
+{
+}
+
+impl<I, K> ContextIterator for DedupByKeyCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Merge adjacent elements of an iterator.
+///
+/// Created by [`ContextIterator::coalesce_with_context`]. The merge function
+/// receives two adjacent elements and the context, and either merges them
+/// into one (`Ok`) or returns them unchanged (`Err`).
+#[derive(Clone, Debug)]
+pub struct CoalesceCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    #[allow(clippy::type_complexity)]
+    pub(crate) merge: fn(I::Item, I::Item, &I::Context) -> Result<I::Item, (I::Item, I::Item)>,
+    pub(crate) last: Option<I::Item>,
+}
+
+impl<I> Iterator for CoalesceCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut last = match self.last.take() {
+            Some(last) => last,
+            None => self.iter.next()?,
+        };
+        while let Some(item) = self.iter.next() {
+            match (self.merge)(last, item, self.iter.context()) {
+                Ok(merged) => last = merged,
+                Err((prev, item)) => {
+                    self.last = Some(item);
+                    return Some(prev);
+                }
+            }
+        }
+        Some(last)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.last.is_some() as usize;
+        (
+            lower.saturating_add(buffered).min(1),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> FusedIterator for CoalesceCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for CoalesceCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Remove consecutive equal elements, keeping the last of each run.
+///
+/// Created by [`ContextIterator::dedup_keep_last_with_context`]. One element
+/// is buffered to detect the end of each run.
+#[derive(Clone, Debug)]
+pub struct DedupLastCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) eq: fn(&I::Item, &I::Item, &I::Context) -> bool,
+    pub(crate) last: Option<I::Item>,
+}
+
+impl<I> Iterator for DedupLastCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut last = match self.last.take() {
+            Some(last) => last,
+            None => self.iter.next()?,
+        };
+        while let Some(item) = self.iter.next() {
+            if !(self.eq)(&last, &item, self.iter.context()) {
+                self.last = Some(item);
+                return Some(last);
+            }
+            last = item;
+        }
+        Some(last)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.last.is_some() as usize;
+        (
+            lower.saturating_add(buffered).min(1),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> FusedIterator for DedupLastCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for DedupLastCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Count the runs of consecutive elements that map to the same key.
+///
+/// Created by [`ContextIterator::run_length_with_context`]. Each key is
+/// computed from an element and the context of the iterator, and each run is
+/// yielded as the key and the number of elements in it.
+#[derive(Clone, Debug)]
+pub struct RunLengthCtx<I, K>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) key: fn(&I::Item, &I::Context) -> K,
+    pub(crate) run: Option<(K, usize)>,
+}
+
+impl<I, K> Iterator for RunLengthCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Item = (K, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut run = match self.run.take() {
+            Some(run) => run,
+            None => {
+                let item = self.iter.next()?;
+                ((self.key)(&item, self.iter.context()), 1)
+            }
+        };
+        while let Some(item) = self.iter.next() {
+            let key = (self.key)(&item, self.iter.context());
+            if key != run.0 {
+                self.run = Some((key, 1));
+                return Some(run);
+            }
+            run.1 += 1;
+        }
+        Some(run)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.run.is_some() as usize;
+        (
+            lower.saturating_add(buffered).min(1),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I, K> FusedIterator for RunLengthCtx<I, K>
+where
+    I: FusedIterator + ContextIterator,
+    K: PartialEq,
+{
+}
+
+impl<I, K> ContextIterator for RunLengthCtx<I, K>
+where
+    I: ContextIterator,
+    K: PartialEq,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn dedup_by_key() {
+        let iter = [1, 2, 4, 5, 6, 9, 3]
+            .into_iter()
+            .with_context(3)
+            .dedup_by_key_with_context(|item: &usize, context: &usize| item / context);
+
+        assert_eq!(iter.context(), &3);
+        assert!(iter.eq([1, 4, 6, 9, 3]));
+    }
+
+    #[test]
+    fn coalesce() {
+        let iter = [(0, 1), (2, 3), (7, 8), (9, 12), (20, 21)]
+            .into_iter()
+            .with_context(1)
+            .coalesce_with_context(|a: (u32, u32), b: (u32, u32), gap: &u32| {
+                if b.0 - a.1 <= *gap {
+                    Ok((a.0, b.1))
+                } else {
+                    Err((a, b))
+                }
+            });
+
+        assert_eq!(iter.context(), &1);
+        assert!(iter.eq([(0, 3), (7, 12), (20, 21)]));
+    }
+
+    #[test]
+    fn dedup_keep_last() {
+        let iter = [(1, 'a'), (1, 'b'), (2, 'c'), (4, 'd'), (4, 'e')]
+            .into_iter()
+            .with_context(())
+            .dedup_keep_last_with_context(|a: &(u32, char), b: &(u32, char), _| a.0 == b.0);
+
+        assert!(iter.eq([(1, 'b'), (2, 'c'), (4, 'e')]));
+    }
+
+    #[test]
+    fn run_length() {
+        let iter = [1, 3, 4, 9, 10, 11, 2]
+            .into_iter()
+            .with_context(5)
+            .run_length_with_context(|item: &u32, context: &u32| item / context);
+
+        assert_eq!(iter.context(), &5);
+        assert!(iter.eq([(0, 3), (1, 1), (2, 2), (0, 1)]));
+
+        let mut iter = (0..0)
+            .with_context(5)
+            .run_length_with_context(|item: &u32, context: &u32| item / context);
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -0,0 +1,251 @@
+//! Context iterators building their context on demand.
+
+use core::cell::OnceCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Wrapper around an iterator adding a context built by a factory.
+///
+/// Created by [`IntoContextIterator::with_context_factory`](crate::IntoContextIterator::with_context_factory).
+///
+/// The context is built on first access. Unlike a [`WithCtx`](crate::WithCtx),
+/// cloning this iterator does not clone the context: each clone builds a fresh
+/// one with the factory the first time it is accessed. This is useful for
+/// contexts holding per-iterator state, such as a scratch buffer.
+pub struct WithCtxFactory<I, Ctx, F> {
+    pub(crate) iter: I,
+    pub(crate) context: OnceCell<Ctx>,
+    pub(crate) factory: F,
+}
+
+impl<I, Ctx, F> Clone for WithCtxFactory<I, Ctx, F>
+where
+    I: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            context: OnceCell::new(),
+            factory: self.factory.clone(),
+        }
+    }
+}
+
+impl<I, Ctx, F> fmt::Debug for WithCtxFactory<I, Ctx, F>
+where
+    I: fmt::Debug,
+    Ctx: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithCtxFactory")
+            .field("iter", &self.iter)
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, Ctx, F> Iterator for WithCtxFactory<I, Ctx, F>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, Ctx, F> DoubleEndedIterator for WithCtxFactory<I, Ctx, F>
+where
+    I: DoubleEndedIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<I, Ctx, F> ExactSizeIterator for WithCtxFactory<I, Ctx, F>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, Ctx, F> FusedIterator for WithCtxFactory<I, Ctx, F> where I: FusedIterator {}
+
+impl<I, Ctx, F> ContextIterator for WithCtxFactory<I, Ctx, F>
+where
+    I: Iterator,
+    F: Fn() -> Ctx,
+{
+    type Context = Ctx;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.context.get_or_init(&self.factory)
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        true
+    }
+}
+
+/// Wrapper around an iterator built from its context.
+///
+/// Created by [`with_context_retry`](crate::with_context_retry).
+///
+/// Cloning this iterator clones the context and rebuilds the inner iterator
+/// from it, instead of cloning the inner iterator. The clone starts again from
+/// the beginning, even if the original was partially consumed. This allows
+/// cloning iterators whose source is cheap to regenerate but not [`Clone`].
+pub struct RebuildCtx<I, Ctx, F> {
+    pub(crate) iter: I,
+    pub(crate) context: Ctx,
+    pub(crate) make: F,
+}
+
+impl<I, Ctx, F> Clone for RebuildCtx<I, Ctx, F>
+where
+    Ctx: Clone,
+    F: Fn(&Ctx) -> I + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: (self.make)(&self.context),
+            context: self.context.clone(),
+            make: self.make.clone(),
+        }
+    }
+}
+
+impl<I, Ctx, F> fmt::Debug for RebuildCtx<I, Ctx, F>
+where
+    I: fmt::Debug,
+    Ctx: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RebuildCtx")
+            .field("iter", &self.iter)
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, Ctx, F> Iterator for RebuildCtx<I, Ctx, F>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, Ctx, F> DoubleEndedIterator for RebuildCtx<I, Ctx, F>
+where
+    I: DoubleEndedIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<I, Ctx, F> ExactSizeIterator for RebuildCtx<I, Ctx, F>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, Ctx, F> FusedIterator for RebuildCtx<I, Ctx, F> where I: FusedIterator {}
+
+impl<I, Ctx, F> ContextIterator for RebuildCtx<I, Ctx, F>
+where
+    I: Iterator,
+{
+    type Context = Ctx;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use crate::*;
+
+    #[test]
+    fn with_context_factory() {
+        thread_local! {
+            static BUILT: Cell<usize> = const { Cell::new(0) };
+        }
+        let factory = || {
+            BUILT.with(|built| built.set(built.get() + 1));
+            vec![0u8; 4]
+        };
+
+        let iter = (0..3).with_context_factory(factory);
+        assert_eq!(BUILT.with(Cell::get), 0);
+        assert_eq!(iter.context().len(), 4);
+        assert_eq!(iter.context().len(), 4);
+        assert_eq!(BUILT.with(Cell::get), 1);
+
+        let clone = iter.clone();
+        assert_eq!(clone.context().len(), 4);
+        assert_eq!(BUILT.with(Cell::get), 2);
+        assert!(clone.eq(0..3));
+    }
+
+    /// An iterator that cannot be cloned.
+    struct Source(core::ops::Range<usize>);
+
+    impl Iterator for Source {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<usize> {
+            self.0.next()
+        }
+    }
+
+    #[test]
+    fn with_context_retry() {
+        let mut iter = crate::with_context_retry(3, |len: &usize| Source(0..*len));
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.next(), Some(0));
+
+        let clone = iter.clone();
+        assert!(clone.eq(0..3));
+        assert!(iter.eq(1..3));
+    }
+}
@@ -0,0 +1,116 @@
+//! End-of-stream handling for stateful adaptors.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Context iterator that may hold back elements until the end of the stream.
+///
+/// Stateful adaptors that buffer elements implement this trait to release
+/// them once the inner iterator is exhausted. Use
+/// [`FinalizableContextIterator::with_finalize`] to append the released
+/// element to the stream.
+pub trait FinalizableContextIterator: ContextIterator {
+    /// Release any element buffered by the iterator.
+    ///
+    /// This is meant to be called once, after `next` has returned `None`.
+    /// Further calls return `None`.
+    ///
+    /// This takes `&mut self` rather than `self`, so that
+    /// [`WithFinalizeCtx`] can keep the iterator, and still give access to
+    /// its context, after the finalized element is released.
+    fn finalize(&mut self) -> Option<Self::Item>;
+
+    /// Append the finalized element to the end of the iterator.
+    fn with_finalize(self) -> WithFinalizeCtx<Self>
+    where
+        Self: Sized,
+    {
+        WithFinalizeCtx {
+            iter: self,
+            finalized: false,
+        }
+    }
+}
+
+/// Append the finalized element of an iterator to its end.
+///
+/// Created by [`FinalizableContextIterator::with_finalize`].
+#[derive(Clone, Debug)]
+pub struct WithFinalizeCtx<I> {
+    pub(self) iter: I,
+    pub(self) finalized: bool,
+}
+
+impl<I> Iterator for WithFinalizeCtx<I>
+where
+    I: FinalizableContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finalized {
+            return None;
+        }
+        self.iter.next().or_else(|| {
+            self.finalized = true;
+            self.iter.finalize()
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finalized {
+            return (0, Some(0));
+        }
+        let (lower, upper) = self.iter.size_hint();
+        (lower, upper.and_then(|upper| upper.checked_add(1)))
+    }
+}
+
+impl<I> FusedIterator for WithFinalizeCtx<I> where I: FinalizableContextIterator {}
+
+impl<I> ContextIterator for WithFinalizeCtx<I>
+where
+    I: FinalizableContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn with_finalize() {
+        let mut iter = (0..2)
+            .with_context(42)
+            .rolling_with_context(3, |window: &[usize], context: &usize| {
+                window.len() + context
+            })
+            .with_finalize();
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.size_hint().1, Some(1));
+        assert_eq!(iter.next(), Some(44));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.context(), &42);
+    }
+}
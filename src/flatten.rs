@@ -0,0 +1,399 @@
+//! Adaptors mapping each element to a sequence of elements.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::VecDeque, vec::Vec};
+use core::iter::FusedIterator;
+
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+
+use crate::ContextIterator;
+
+/// Map each element to a context iterator and flatten the result.
+///
+/// Created by [`ContextIterator::flat_map_subcontext`].
+///
+/// The context is resolved in the following order:
+///
+/// 1. The context of the active sub-iterator, if there is one. A sub-iterator
+///    is active from its creation until a call to `next` finds it exhausted.
+/// 2. The context of the outer iterator otherwise.
+#[derive(Clone, Debug)]
+pub struct FlatMapSubCtx<I, J>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) map: fn(I::Item, &I::Context) -> J,
+    pub(crate) inner: Option<J>,
+}
+
+impl<I, J> Iterator for FlatMapSubCtx<I, J>
+where
+    I: ContextIterator,
+    J: ContextIterator<Context = I::Context>,
+{
+    type Item = J::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(inner) = &mut self.inner {
+                if let Some(item) = inner.next() {
+                    return Some(item);
+                }
+                self.inner = None;
+            }
+            let item = self.iter.next()?;
+            self.inner = Some((self.map)(item, self.iter.context()));
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self
+            .inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint());
+        match self.iter.size_hint() {
+            (_, Some(0)) => (lower, upper),
+            _ => (lower, None),
+        }
+    }
+}
+
+impl<I, J> FusedIterator for FlatMapSubCtx<I, J>
+where
+    I: FusedIterator + ContextIterator,
+    J: ContextIterator<Context = I::Context>,
+{
+}
+
+impl<I, J> ContextIterator for FlatMapSubCtx<I, J>
+where
+    I: ContextIterator,
+    J: ContextIterator<Context = I::Context>,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        match &self.inner {
+            Some(inner) => inner.context(),
+            None => self.iter.context(),
+        }
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        match &self.inner {
+            Some(inner) => inner.context_chain_depth(),
+            None => self.iter.context_chain_depth(),
+        }
+    }
+}
+
+/// Map each element and its index to an iterable, and flatten the result.
+///
+/// Created by [`ContextIterator::flat_map_indexed_with_context`]. The index
+/// counts the elements of the outer iterator.
+#[derive(Clone, Debug)]
+pub struct FlatMapIndexedCtx<I, U>
+where
+    I: ContextIterator,
+    U: IntoIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) map: fn(usize, I::Item, &I::Context) -> U,
+    pub(crate) index: usize,
+    pub(crate) inner: Option<U::IntoIter>,
+}
+
+impl<I, U> FlatMapIndexedCtx<I, U>
+where
+    I: ContextIterator,
+    U: IntoIterator,
+{
+    /// Set the index back to zero, so the next outer element gets index `0`.
+    ///
+    /// Cloning the adaptor copies the current index, so this is useful to
+    /// reuse a cloned pipeline for a fresh batch.
+    #[inline]
+    pub fn reset_index(&mut self) {
+        self.index = 0;
+    }
+}
+
+impl<I, U> Iterator for FlatMapIndexedCtx<I, U>
+where
+    I: ContextIterator,
+    U: IntoIterator,
+{
+    type Item = U::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(inner) = &mut self.inner {
+                if let Some(item) = inner.next() {
+                    return Some(item);
+                }
+                self.inner = None;
+            }
+            let item = self.iter.next()?;
+            let index = self.index;
+            self.index += 1;
+            self.inner = Some((self.map)(index, item, self.iter.context()).into_iter());
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<I, U> FusedIterator for FlatMapIndexedCtx<I, U>
+where
+    I: FusedIterator + ContextIterator,
+    U: IntoIterator,
+{
+}
+
+impl<I, U> ContextIterator for FlatMapIndexedCtx<I, U>
+where
+    I: ContextIterator,
+    U: IntoIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Map each element to a sequence of elements pushed into a reused buffer,
+/// and flatten the result.
+///
+/// Created by [`ContextIterator::flat_map_scratch_with_context`]. The buffer
+/// is drained before the function is called again, so it only grows to the
+/// largest number of outputs of a single element.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct FlatMapScratchCtx<I, O>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) f: fn(I::Item, &I::Context, &mut Vec<O>),
+    /// The pending outputs. The front of the deque is the read cursor; the
+    /// buffer is handed back to `f` as a `Vec` once it is exhausted.
+    pub(crate) scratch: VecDeque<O>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, O> Iterator for FlatMapScratchCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.scratch.pop_front() {
+                return Some(item);
+            }
+            let item = self.iter.next()?;
+            // Both conversions keep the allocation and, with the deque empty,
+            // move no elements.
+            let mut scratch = Vec::from(core::mem::take(&mut self.scratch));
+            scratch.clear();
+            (self.f)(item, self.iter.context(), &mut scratch);
+            self.scratch = scratch.into();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I, O> FusedIterator for FlatMapScratchCtx<I, O> where I: FusedIterator + ContextIterator {}
+
+#[cfg(feature = "alloc")]
+impl<I, O> ContextIterator for FlatMapScratchCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Map each element to a sequence of elements pushed into a small inline
+/// buffer, and flatten the result.
+///
+/// Created by [`ContextIterator::expand_with_context`]. Each element gets an
+/// empty buffer, and its outputs are yielded before the function is called
+/// again.
+#[cfg(feature = "smallvec")]
+#[derive(Clone, Debug)]
+pub struct ExpandCtx<I, O>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    #[allow(clippy::type_complexity)]
+    pub(crate) f: fn(I::Item, &I::Context, &mut SmallVec<[O; 4]>),
+    /// The pending outputs, read in order through the iterator's cursor.
+    pub(crate) buffer: smallvec::IntoIter<[O; 4]>,
+}
+
+#[cfg(feature = "smallvec")]
+impl<I, O> Iterator for ExpandCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.next() {
+                return Some(item);
+            }
+            let item = self.iter.next()?;
+            let mut buffer = SmallVec::new();
+            (self.f)(item, self.iter.context(), &mut buffer);
+            self.buffer = buffer.into_iter();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<I, O> FusedIterator for ExpandCtx<I, O> where I: FusedIterator + ContextIterator {}
+
+#[cfg(feature = "smallvec")]
+impl<I, O> ContextIterator for ExpandCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::ops::Range;
+
+    use crate::*;
+
+    #[test]
+    fn flat_map_subcontext() {
+        let mut iter = (1..3).with_context(0).flat_map_subcontext(
+            |item: usize, context: &usize| -> WithCtx<Range<usize>, usize> {
+                (*context..item).with_context(item * 10)
+            },
+        );
+
+        assert_eq!(iter.context(), &0);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.context(), &10);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.context(), &20);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.context(), &0);
+    }
+
+    #[test]
+    fn flat_map_indexed() {
+        let iter = ['a', 'b', 'c']
+            .into_iter()
+            .with_context(1)
+            .flat_map_indexed_with_context(|index, item: char, context: &usize| {
+                core::iter::repeat(item).take(index + context)
+            });
+
+        assert_eq!(iter.context(), &1);
+        assert!(iter.eq("abbccc".chars()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn flat_map_scratch() {
+        let mut iter = ["ab", "", "cde"]
+            .into_iter()
+            .with_context('-')
+            .flat_map_scratch_with_context(|item: &str, context: &char, out| {
+                for c in item.chars() {
+                    out.push(c);
+                    out.push(*context);
+                }
+            });
+
+        assert_eq!(iter.context(), &'-');
+        assert_eq!(iter.next(), Some('a'));
+        assert!(iter.eq("-b-c-d-e-".chars()));
+    }
+
+    #[test]
+    #[cfg(feature = "smallvec")]
+    fn expand() {
+        let iter =
+            (0..5)
+                .with_context(3)
+                .expand_with_context(|item: usize, context: &usize, out| {
+                    out.extend(core::iter::repeat(item).take(item % context))
+                });
+
+        assert_eq!(iter.context(), &3);
+        assert!(iter.eq([1, 2, 2, 4]));
+    }
+}
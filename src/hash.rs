@@ -0,0 +1,373 @@
+//! Adaptors using hash-based collections.
+
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+
+use crate::ContextIterator;
+
+/// Translate the elements of an iterator using a map stored in the context.
+///
+/// Created by [`ContextIterator::lookup_with_context`]. Elements whose key is
+/// missing from the map are dropped.
+#[derive(Clone, Debug)]
+pub struct LookupCtx<I, K>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) key: fn(&I::Item) -> &K,
+}
+
+impl<I, K, V, S> Iterator for LookupCtx<I, K>
+where
+    I: ContextIterator<Context = HashMap<K, V, S>>,
+    K: Eq + Hash,
+    V: Clone,
+    S: BuildHasher,
+{
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if let Some(value) = self.iter.context().get((self.key)(&item)) {
+                return Some(value.clone());
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, K, V, S> FusedIterator for LookupCtx<I, K>
+where
+    I: FusedIterator + ContextIterator<Context = HashMap<K, V, S>>,
+    K: Eq + Hash,
+    V: Clone,
+    S: BuildHasher,
+{
+}
+
+impl<I, K, V, S> ContextIterator for LookupCtx<I, K>
+where
+    I: ContextIterator<Context = HashMap<K, V, S>>,
+    K: Eq + Hash,
+    V: Clone,
+    S: BuildHasher,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Pair each element of an iterator with a hash of the context.
+///
+/// Created by [`ContextIterator::with_context_hash`]. The hash is computed
+/// after each element is read, unless the context is constant, in which case
+/// the first hash is cached and reused.
+#[derive(Clone, Debug)]
+pub struct ContextHashCtx<I> {
+    pub(crate) iter: I,
+    pub(crate) cached: Option<u64>,
+}
+
+impl<I> Iterator for ContextHashCtx<I>
+where
+    I: ContextIterator,
+    I::Context: Hash,
+{
+    type Item = (I::Item, u64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let hash = match self.cached {
+            Some(hash) => hash,
+            None => {
+                let mut hasher = DefaultHasher::new();
+                self.iter.context().hash(&mut hasher);
+                let hash = hasher.finish();
+                if self.iter.context_is_constant() {
+                    self.cached = Some(hash);
+                }
+                hash
+            }
+        };
+        Some((item, hash))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ContextHashCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Context: Hash,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for ContextHashCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Context: Hash,
+{
+}
+
+impl<I> ContextIterator for ContextHashCtx<I>
+where
+    I: ContextIterator,
+    I::Context: Hash,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Count the occurrences of each key into a map stored in the context.
+///
+/// Created by [`WithCtx::tally_into_context`](crate::WithCtx::tally_into_context).
+///
+/// The elements are yielded unchanged, and each one is counted before it is
+/// returned, so the context always holds the counts of the elements yielded
+/// so far.
+#[derive(Clone, Debug)]
+pub struct TallyCtx<I, K, S>
+where
+    I: Iterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: HashMap<K, usize, S>,
+    pub(crate) key: fn(&I::Item) -> K,
+}
+
+impl<I, K, S> TallyCtx<I, K, S>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Drive the iterator to the end, returning the filled map.
+    pub fn into_tally(mut self) -> HashMap<K, usize, S> {
+        self.by_ref().for_each(drop);
+        self.context
+    }
+}
+
+impl<I, K, S> Iterator for TallyCtx<I, K, S>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        *self.context.entry((self.key)(&item)).or_insert(0) += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, K, S> ExactSizeIterator for TallyCtx<I, K, S>
+where
+    I: ExactSizeIterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, K, S> FusedIterator for TallyCtx<I, K, S>
+where
+    I: FusedIterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<I, K, S> ContextIterator for TallyCtx<I, K, S>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Context = HashMap<K, usize, S>;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+/// Yield the first element for each key, tracking the seen keys in a set
+/// stored in the context.
+///
+/// Created by [`WithCtx::unique_with_context`](crate::WithCtx::unique_with_context).
+///
+/// The context holds the keys of the elements yielded so far.
+#[derive(Clone, Debug)]
+pub struct UniqueCtx<I, K, S>
+where
+    I: Iterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: HashSet<K, S>,
+    pub(crate) key: fn(&I::Item) -> K,
+}
+
+impl<I, K, S> Iterator for UniqueCtx<I, K, S>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if self.context.insert((self.key)(&item)) {
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, K, S> FusedIterator for UniqueCtx<I, K, S>
+where
+    I: FusedIterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<I, K, S> ContextIterator for UniqueCtx<I, K, S>
+where
+    I: Iterator,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Context = HashSet<K, S>;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::*;
+
+    #[test]
+    fn lookup() {
+        let names = HashMap::from([(1, "one"), (3, "three")]);
+        let iter = [(1, 'a'), (2, 'b'), (3, 'c')]
+            .into_iter()
+            .with_context(names)
+            .lookup_with_context(|item: &(u32, char)| &item.0);
+
+        assert_eq!(iter.context().len(), 2);
+        assert!(iter.eq(["one", "three"]));
+    }
+
+    #[test]
+    fn tally() {
+        let mut iter = ["a", "bb", "c", "dd", "e"]
+            .into_iter()
+            .with_context(HashMap::new())
+            .tally_into_context(|item: &&str| item.len());
+
+        assert_eq!(iter.next(), Some("a"));
+        assert_eq!(iter.context(), &HashMap::from([(1, 1)]));
+
+        let tally = iter.into_tally();
+        assert_eq!(tally, HashMap::from([(1, 3), (2, 2)]));
+    }
+
+    #[test]
+    fn unique() {
+        let mut iter = ["a", "bb", "c", "dd", "eee"]
+            .into_iter()
+            .with_context(HashSet::from([3]))
+            .unique_with_context(|item: &&str| item.len());
+
+        assert_eq!(iter.next(), Some("a"));
+        assert_eq!(iter.context(), &HashSet::from([1, 3]));
+        assert!(iter.eq(["bb"]));
+    }
+
+    #[test]
+    fn context_hash() {
+        let mut iter = (0..3)
+            .with_context(7)
+            .map_with_context(|item: u32, context: &u32| item * context)
+            .with_context_hash();
+        let (_, first) = iter.next().unwrap();
+        assert_eq!(iter.cached, Some(first));
+        assert!(iter.eq([(7, first), (14, first)]));
+
+        let mut iter = (0..2)
+            .with_context(7)
+            .update_context_each(|context| *context += 1)
+            .with_context_hash();
+        let (_, first) = iter.next().unwrap();
+        let (_, second) = iter.next().unwrap();
+        assert_eq!(iter.cached, None);
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,362 @@
+//! Adaptors observing the iteration without changing the elements.
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Report the progress of an iterator after each element.
+///
+/// Created by [`ContextIterator::progress_with_context`]. After each yielded
+/// element, the report function receives the number of elements yielded so
+/// far, the upper bound of the remaining elements, and the context.
+///
+/// The count only includes the elements yielded by this adaptor, not the
+/// ones consumed by earlier stages of the pipeline, such as elements dropped
+/// by a filter.
+#[derive(Clone, Debug)]
+pub struct ProgressCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) report: fn(usize, Option<usize>, &I::Context),
+    pub(crate) produced: usize,
+}
+
+impl<I> Iterator for ProgressCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.produced += 1;
+        (self.report)(self.produced, self.iter.size_hint().1, self.iter.context());
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ProgressCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for ProgressCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for ProgressCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Call hooks for each element of an iterator and at its end.
+///
+/// Created by [`ContextIterator::metered_with_context`]. The end hook is
+/// called with the number of yielded elements the first time `next` returns
+/// `None`, and the adaptor stays exhausted afterwards.
+#[derive(Clone, Debug)]
+pub struct MeteredCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) on_item: fn(&I::Context),
+    pub(crate) on_end: fn(usize, &I::Context),
+    pub(crate) count: usize,
+    pub(crate) done: bool,
+}
+
+impl<I> Iterator for MeteredCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.iter.next() {
+            Some(item) => {
+                self.count += 1;
+                (self.on_item)(self.iter.context());
+                Some(item)
+            }
+            None => {
+                self.done = true;
+                (self.on_end)(self.count, self.iter.context());
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            true => (0, Some(0)),
+            false => self.iter.size_hint(),
+        }
+    }
+}
+
+impl<I> ExactSizeIterator for MeteredCtx<I> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I> FusedIterator for MeteredCtx<I> where I: ContextIterator {}
+
+impl<I> ContextIterator for MeteredCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Call a function whenever the context of an iterator changes.
+///
+/// Created by [`ContextIterator::on_context_change`]. Before each element is
+/// yielded, its context is compared with the one of the previous element, and
+/// the function is called with both if they differ. There is no previous
+/// context for the first element, so it never triggers the function.
+#[derive(Clone, Debug)]
+pub struct ContextChangeCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) f: fn(&I::Context, &I::Context),
+    pub(crate) last: Option<I::Context>,
+}
+
+impl<I> Iterator for ContextChangeCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let context = self.iter.context();
+        match &self.last {
+            Some(last) if last == context => {}
+            Some(last) => {
+                (self.f)(last, context);
+                self.last = Some(context.clone());
+            }
+            None => self.last = Some(context.clone()),
+        }
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ContextChangeCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for ContextChangeCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+}
+
+impl<I> ContextIterator for ContextChangeCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// A description of the context of an iterator, for debugging.
+///
+/// Created by [`ContextIterator::describe_context`]. It borrows the context,
+/// so creating it is cheap, and the context is only formatted when the
+/// description is.
+pub struct ContextDescription<'a, C: ?Sized> {
+    pub(crate) depth: usize,
+    pub(crate) context: &'a C,
+}
+
+impl<'a, C: ?Sized> ContextDescription<'a, C> {
+    /// The number of [`CtxMap`](crate::CtxMap) projections applied to the
+    /// context.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The context, after all the projections.
+    pub fn context(&self) -> &'a C {
+        self.context
+    }
+}
+
+impl<C: ?Sized> Clone for ContextDescription<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ?Sized> Copy for ContextDescription<'_, C> {}
+
+impl<C> fmt::Debug for ContextDescription<'_, C>
+where
+    C: fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextDescription")
+            .field("depth", &self.depth)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use crate::*;
+
+    thread_local! {
+        static REPORTS: RefCell<Vec<(usize, Option<usize>)>> = const { RefCell::new(Vec::new()) };
+        static METRICS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        static CHANGES: RefCell<Vec<(usize, usize)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[test]
+    fn progress() {
+        let iter = (0..6)
+            .with_context("label")
+            .filter_with_context(|item: &usize, _| item % 2 == 0)
+            .progress_with_context(|produced, remaining, label: &&str| {
+                assert_eq!(*label, "label");
+                REPORTS.with(|reports| reports.borrow_mut().push((produced, remaining)));
+            });
+
+        assert_eq!(iter.context(), &"label");
+        assert!(iter.eq([0, 2, 4]));
+        REPORTS.with(|reports| {
+            assert_eq!(
+                *reports.borrow(),
+                [(1, Some(5)), (2, Some(3)), (3, Some(1))]
+            );
+        });
+    }
+
+    #[test]
+    fn metered() {
+        let mut iter = (0..2).with_context("tag").metered_with_context(
+            |tag: &&str| METRICS.with(|m| m.borrow_mut().push(format!("{tag}: item"))),
+            |count, tag| METRICS.with(|m| m.borrow_mut().push(format!("{tag}: {count} items"))),
+        );
+
+        assert_eq!(iter.context(), &"tag");
+        assert!(iter.by_ref().eq(0..2));
+        assert_eq!(iter.next(), None);
+        METRICS.with(|metrics| {
+            assert_eq!(
+                *metrics.borrow(),
+                ["tag: item", "tag: item", "tag: 2 items"]
+            );
+        });
+    }
+
+    #[test]
+    fn on_context_change() {
+        let iter = (0..5)
+            .with_context(0)
+            .update_context_each(|context: &mut usize| *context += 1)
+            .context_map(|context: &usize| context)
+            .filter_with_context(|item: &usize, _| item % 2 == 0)
+            .on_context_change(|old, new| CHANGES.with(|c| c.borrow_mut().push((*old, *new))));
+
+        assert!(iter.eq([0, 2, 4]));
+        CHANGES.with(|changes| assert_eq!(*changes.borrow(), [(0, 2), (2, 4)]));
+    }
+
+    #[test]
+    fn describe_context() {
+        let iter = (0..3)
+            .with_context((1, ("name", 2)))
+            .context_map(|context: &(usize, (&str, usize))| &context.1)
+            .context_map(|context: &(&str, usize)| &context.0);
+
+        let description = iter.describe_context();
+        assert_eq!(description.depth(), 2);
+        assert_eq!(description.context(), &"name");
+        assert_eq!(
+            format!("{description:?}"),
+            r#"ContextDescription { depth: 2, context: "name" }"#
+        );
+    }
+}
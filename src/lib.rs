@@ -1,3 +1,4 @@
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![warn(missing_docs)]
 //! Iterators adaptors with associated read-only data.
 //!
@@ -34,7 +35,100 @@
 //! }
 //! ```
 
-use std::iter::FusedIterator;
+use core::fmt::Debug;
+use core::iter::FusedIterator;
+use core::ops::{Add, Mul, Sub};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+mod accumulate;
+mod annotate;
+#[cfg(feature = "alloc")]
+mod boxed;
+mod chain;
+#[cfg(feature = "alloc")]
+mod chunks;
+mod dedup;
+mod factory;
+mod finalize;
+mod flatten;
+#[cfg(feature = "std")]
+mod hash;
+mod inspect;
+mod map;
+mod mutate;
+mod owned;
+#[cfg(feature = "rayon")]
+mod par;
+mod position;
+mod project;
+mod resize;
+mod result;
+#[cfg(feature = "rand")]
+mod sample;
+#[cfg(feature = "alloc")]
+mod select;
+#[cfg(feature = "async")]
+mod stream;
+mod take;
+#[cfg(feature = "alloc")]
+mod tee;
+mod validate;
+mod zip;
+
+pub use accumulate::{AccumulateCtx, PrefixSumCtx};
+pub use annotate::{AnnotateCtx, Annotated};
+#[cfg(feature = "alloc")]
+pub use boxed::CloneableContextIterator;
+pub use chain::{GroupSepCtx, InterpolateCtx, PrependCtxCtx, TrailerCtx};
+#[cfg(feature = "alloc")]
+pub use chunks::{ArrayChunksCtx, ChunkReduceCtx, ChunksFromCtxCtx, RollingCtx};
+pub use dedup::{CoalesceCtx, DedupByKeyCtx, DedupLastCtx, RunLengthCtx};
+pub use factory::{RebuildCtx, WithCtxFactory};
+pub use finalize::{FinalizableContextIterator, WithFinalizeCtx};
+#[cfg(feature = "smallvec")]
+pub use flatten::ExpandCtx;
+#[cfg(feature = "alloc")]
+pub use flatten::FlatMapScratchCtx;
+pub use flatten::{FlatMapIndexedCtx, FlatMapSubCtx};
+#[cfg(feature = "std")]
+pub use hash::{ContextHashCtx, LookupCtx, TallyCtx, UniqueCtx};
+pub use inspect::{ContextChangeCtx, ContextDescription, MeteredCtx, ProgressCtx};
+pub use map::{DistanceCtx, MapAlternatingCtx, MapIfCtx, RekeyCtx};
+pub use mutate::{ScanContextCtx, UpdateCtx};
+pub use owned::{CtxMapOwned, OwnedContextIterator};
+#[cfg(feature = "rayon")]
+pub use par::{
+    ContextParallelIterator, FilterCtxPar, IntoContextParallelIterator, MapCtxPar, WithCtxPar,
+};
+pub use position::{
+    EnumeratePairsCtx, FilterIndexedCtx, LookaheadCtx, NumberedCtx, SkipEveryNthCtx,
+    StepByTrackedCtx, WithRemainingCtx,
+};
+pub use project::{CtxMapOpt, WithProjectedCtx};
+pub use resize::{ClampHintCtx, ResizeCtx};
+pub use result::RecoverCtx;
+#[cfg(feature = "rand")]
+pub use sample::SampleCtx;
+#[cfg(feature = "alloc")]
+pub use select::SortWindowCtx;
+#[cfg(feature = "async")]
+pub use stream::{ContextStream, FilterCtxStream, IntoContextStream, MapCtxStream, WithCtxStream};
+pub use take::{GateCtx, LimitCtx, TakeUntilCtx, TakeUntilCtxChangeCtx};
+#[cfg(feature = "alloc")]
+pub use tee::TeeCtx;
+pub use validate::{AssertSortedCtx, ValidateCtx};
+pub use zip::{EitherOrBoth, InterleaveCtx, ZipCheckedCtx, ZipEqCtx, ZipLongestCtx};
 
 /// Extended iterator trait to allow adding context data.
 ///
@@ -50,58 +144,2031 @@ pub trait IntoContextIterator: Iterator {
             context,
         }
     }
+
+    /// Add a context built on demand by a factory.
+    ///
+    /// The context is built on first access. Cloning the iterator does not
+    /// clone the context; each clone builds its own with the factory instead.
+    /// See [`WithCtxFactory`].
+    fn with_context_factory<Ctx, F>(self, factory: F) -> WithCtxFactory<Self, Ctx, F>
+    where
+        Self: Sized,
+        F: Fn() -> Ctx,
+    {
+        WithCtxFactory {
+            iter: self,
+            context: core::cell::OnceCell::new(),
+            factory,
+        }
+    }
+
+    /// Re-attach a context to the iterator.
+    ///
+    /// This is equivalent to [`IntoContextIterator::with_context`], named to
+    /// signal that the context is being restored after an adaptor that does
+    /// not preserve it. See also [`ContextIterator::carry_context`].
+    fn recontextualize<Ctx>(self, context: Ctx) -> WithCtx<Self, Ctx>
+    where
+        Self: Sized,
+    {
+        self.with_context(context)
+    }
+
+    /// Add two read-only values to the iterator, as a tuple context.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = (0..3)
+    ///     .with_contexts(10, 1)
+    ///     .map_with_context(|item: u32, (scale, offset): &(u32, u32)| item * scale + offset);
+    ///
+    /// assert!(iter.eq([1, 11, 21]));
+    /// ```
+    fn with_contexts<A, B>(self, a: A, b: B) -> WithCtx<Self, (A, B)>
+    where
+        Self: Sized,
+    {
+        self.with_context((a, b))
+    }
+
+    /// Use the first element of the iterator to build the context for the
+    /// remaining ones.
+    ///
+    /// The first element is consumed. Returns `None` if the iterator is empty.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let rows = ["a,b", "1,2", "3,4"].into_iter();
+    /// let iter = rows
+    ///     .with_context_from_first(|header: &&str| header.split(',').count())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(iter.context(), &2);
+    /// assert!(iter.eq(["1,2", "3,4"]));
+    /// ```
+    fn with_context_from_first<F, Ctx>(mut self, f: F) -> Option<WithCtx<Self, Ctx>>
+    where
+        Self: Sized,
+        F: FnOnce(&Self::Item) -> Ctx,
+    {
+        let first = self.next()?;
+        Some(self.with_context(f(&first)))
+    }
 }
 
 impl<I> IntoContextIterator for I where I: Iterator {}
 
+/// Create an empty iterator with a context.
+///
+/// Useful for returning a context iterator of the right type from a branch
+/// with no elements.
+///
+/// ```
+/// # use context_iterators::*;
+/// let iter = empty_with_context::<usize, _>(42);
+///
+/// assert_eq!(iter.context(), &42);
+/// assert_eq!(iter.len(), 0);
+/// ```
+pub fn empty_with_context<T, Ctx>(context: Ctx) -> WithCtx<core::iter::Empty<T>, Ctx> {
+    core::iter::empty().with_context(context)
+}
+
+/// Create an iterator yielding a single element, with a context.
+///
+/// ```
+/// # use context_iterators::*;
+/// let iter = once_with_context(1, 42).map_with_context(|item: usize, context: &usize| item + context);
+///
+/// assert_eq!(iter.context(), &42);
+/// assert!(iter.eq([43]));
+/// ```
+pub fn once_with_context<T, Ctx>(item: T, context: Ctx) -> WithCtx<core::iter::Once<T>, Ctx> {
+    core::iter::once(item).with_context(context)
+}
+
+/// Build an iterator from a context, rebuilding it when cloned.
+///
+/// The inner iterator is built by calling `make` with the context. Cloning
+/// the result clones the context and calls `make` again, so the source does
+/// not need to implement [`Clone`]. See [`RebuildCtx`].
+///
+/// ```
+/// # use context_iterators::*;
+/// let iter = with_context_retry(4, |len: &usize| (0..*len).map(|i| i * 2));
+///
+/// assert_eq!(iter.context(), &4);
+/// assert!(iter.clone().eq([0, 2, 4, 6]));
+/// ```
+pub fn with_context_retry<Ctx, F, I>(context: Ctx, make: F) -> RebuildCtx<I, Ctx, F>
+where
+    F: Fn(&Ctx) -> I,
+    I: Iterator,
+{
+    RebuildCtx {
+        iter: make(&context),
+        context,
+        make,
+    }
+}
+
+/// Split a string by a delimiter read from the context.
+///
+/// The delimiter is read once, when the iterator is created. It is a `char`
+/// so that the iterator does not borrow from the context it carries.
+///
+/// ```
+/// # use context_iterators::*;
+/// let iter = split_with_context("a,b,c", (',', 3), |(delimiter, _)| *delimiter);
+///
+/// assert_eq!(iter.context(), &(',', 3));
+/// assert!(iter.eq(["a", "b", "c"]));
+/// ```
+pub fn split_with_context<Ctx>(
+    input: &str,
+    context: Ctx,
+    delimiter: fn(&Ctx) -> char,
+) -> WithCtx<core::str::Split<'_, char>, Ctx> {
+    let delimiter = delimiter(&context);
+    input.split(delimiter).with_context(context)
+}
+
+/// Iterate over a vector, with its original length as the context.
+///
+/// The context is not updated as the elements are consumed or filtered out,
+/// so it always reports how many elements there were to begin with.
+///
+/// ```
+/// # use context_iterators::*;
+/// let iter = from_vec_len_context(vec!['a', 'b', 'c']);
+///
+/// assert_eq!(iter.context(), &3);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn from_vec_len_context<T>(v: Vec<T>) -> WithCtx<vec::IntoIter<T>, usize> {
+    let len = v.len();
+    v.into_iter().with_context(len)
+}
+
 /// Iterator carrying a context.
 pub trait ContextIterator: Iterator {
     /// The context type.
     type Context;
 
-    /// Get the context.
-    fn context(&self) -> &Self::Context;
+    /// Get the context.
+    fn context(&self) -> &Self::Context;
+
+    /// Run a function with a reference to the context, returning its result.
+    ///
+    /// The reference cannot escape the closure, which makes it clear that it
+    /// may not be held across calls to `next`. Use it to extract owned values
+    /// from the context.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let mut iter = (0..3)
+    ///     .with_context(("label", 42))
+    ///     .context_map(|context: &(&str, usize)| &context.0);
+    ///
+    /// let label: String = iter.context_ref_scoped(|label| label.to_string());
+    /// iter.next();
+    /// assert_eq!(label, "label");
+    /// ```
+    #[inline]
+    fn context_ref_scoped<R, F>(&self, f: F) -> R
+    where
+        Self: Sized,
+        F: FnOnce(&Self::Context) -> R,
+    {
+        f(self.context())
+    }
+
+    /// Get the number of [`CtxMap`] projections applied to the context.
+    ///
+    /// Iterators created with [`IntoContextIterator::with_context`] have a
+    /// depth of zero. Adaptors that forward the context forward the depth of
+    /// their inner iterator.
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        0
+    }
+
+    /// Whether the context is guaranteed to never change during the
+    /// iteration.
+    ///
+    /// This is `true` for iterators created with
+    /// [`IntoContextIterator::with_context`], and adaptors that forward the
+    /// context forward it from their inner iterator. Adaptors whose context
+    /// may evolve return `false`, the default. Adaptors such as
+    /// [`ContextIterator::with_context_hash`] use it to compute values derived
+    /// from the context only once.
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        false
+    }
+
+    /// Describe the context and the number of projections applied to it, for
+    /// debugging.
+    ///
+    /// The description borrows the context, and only formats it when the
+    /// description is formatted with [`Debug`].
+    fn describe_context(&self) -> ContextDescription<'_, Self::Context> {
+        ContextDescription {
+            depth: self.context_chain_depth(),
+            context: self.context(),
+        }
+    }
+
+    /// Take a snapshot of the current context, returned along with the
+    /// iterator.
+    ///
+    /// Only the context is saved, not the position of the iterator. The
+    /// snapshot can later be applied to a fresh iterator with
+    /// [`Checkpoint::restore_context`], to go back to the configuration of an
+    /// earlier point.
+    fn checkpoint(self) -> (Checkpoint<Self::Context>, Self)
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let checkpoint = Checkpoint {
+            context: self.context().clone(),
+        };
+        (checkpoint, self)
+    }
+
+    /// Get the context.
+    fn context_map<F, O>(self, map: F) -> CtxMap<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Context) -> &O,
+    {
+        CtxMap { iter: self, map }
+    }
+
+    /// Get the context, after checking that the projection is valid.
+    ///
+    /// The `validate` function is called once on the current context, and
+    /// its error is returned if it fails. Otherwise, `map` is installed as in
+    /// [`ContextIterator::context_map`]. Keeping the projection infallible
+    /// means accessing the context never has to deal with the error.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = (0..3).with_context([1, 2]);
+    ///
+    /// let iter = iter
+    ///     .try_context_map(
+    ///         |context: &[usize; 2]| match context[1] {
+    ///             0 => Err("zero divisor"),
+    ///             _ => Ok(()),
+    ///         },
+    ///         |context: &[usize; 2]| &context[1],
+    ///     )
+    ///     .unwrap();
+    ///
+    /// assert_eq!(iter.context(), &2);
+    /// ```
+    fn try_context_map<V, F, O, E>(self, validate: V, map: F) -> Result<CtxMap<Self, F>, E>
+    where
+        Self: Sized,
+        V: FnOnce(&Self::Context) -> Result<(), E>,
+        F: Fn(&Self::Context) -> &O,
+    {
+        validate(self.context())?;
+        Ok(self.context_map(map))
+    }
+
+    /// Apply a function to the context that may fail to find the projected
+    /// value.
+    ///
+    /// The result does not implement [`ContextIterator`], since the context
+    /// may be missing. Use [`CtxMapOpt::context_opt`] to access it.
+    fn context_map_opt<F, O>(self, map: F) -> CtxMapOpt<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Context) -> Option<&O>,
+    {
+        CtxMapOpt { iter: self, map }
+    }
+
+    /// Apply a function to the context, producing an owned value.
+    ///
+    /// The result implements [`OwnedContextIterator`] instead of
+    /// [`ContextIterator`], and recomputes the context each time it is
+    /// requested.
+    fn context_map_owned<F, O>(self, map: F) -> CtxMapOwned<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Self::Context) -> O,
+    {
+        CtxMapOwned { iter: self, map }
+    }
+
+    /// Apply a map to each element in the iterator.
+    fn map_with_context<O>(self, map: fn(Self::Item, &Self::Context) -> O) -> MapCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        MapCtx { iter: self, map }
+    }
+
+    /// Pair each element with a clone of a projection of the context.
+    ///
+    /// This combines [`ContextIterator::context_map`] and
+    /// [`ContextIterator::map_with_context`] to give each element a snapshot
+    /// of a field of the context. The projected value is cloned for every
+    /// element, so it should be cheap to clone.
+    fn items_with_projected_context<O>(
+        self,
+        project: fn(&Self::Context) -> &O,
+    ) -> WithProjectedCtx<Self, O>
+    where
+        Self: Sized,
+        O: Clone,
+    {
+        WithProjectedCtx {
+            iter: self,
+            project,
+        }
+    }
+
+    /// Multiply each element by the context.
+    ///
+    /// This is a [`MapCtx`], so the output type can be named.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter: MapCtx<_, f64> = [1.0, 2.0, 3.0]
+    ///     .into_iter()
+    ///     .with_context(0.5)
+    ///     .scale_with_context();
+    ///
+    /// assert!(iter.eq([0.5, 1.0, 1.5]));
+    /// ```
+    fn scale_with_context(self) -> MapCtx<Self, Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Mul<Self::Context, Output = Self::Item>,
+        Self::Context: Copy,
+    {
+        self.map_with_context(|item, context| item * *context)
+    }
+
+    /// Subtract the context from each element.
+    ///
+    /// Useful to center the elements around a baseline stored in the context.
+    /// This is a [`MapCtx`], so the output type can be named.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter: MapCtx<_, i32> = [8, 10, 13]
+    ///     .into_iter()
+    ///     .with_context(10)
+    ///     .delta_from_context();
+    ///
+    /// assert!(iter.eq([-2, 0, 3]));
+    /// ```
+    fn delta_from_context(self) -> MapCtx<Self, Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Sub<Self::Context, Output = Self::Item>,
+        Self::Context: Copy,
+    {
+        self.map_with_context(|item, baseline| item - *baseline)
+    }
+
+    /// Pair each element with a hash of the context.
+    ///
+    /// Downstream stages can compare the hashes to cheaply detect changes in
+    /// the context, e.g. to invalidate caches. The hash is computed with
+    /// [`DefaultHasher`](std::collections::hash_map::DefaultHasher), so it is
+    /// only stable within a single build of the program.
+    ///
+    /// If [`ContextIterator::context_is_constant`] holds, the context is
+    /// hashed once and the hash is reused. Otherwise it is hashed again for
+    /// each element.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let mut iter = (0..2).with_context("config").with_context_hash();
+    ///
+    /// let (_, first) = iter.next().unwrap();
+    /// let (_, second) = iter.next().unwrap();
+    /// assert_eq!(first, second);
+    /// ```
+    #[cfg(feature = "std")]
+    fn with_context_hash(self) -> ContextHashCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: Hash,
+    {
+        ContextHashCtx {
+            iter: self,
+            cached: None,
+        }
+    }
+
+    /// Multiply each point by the 2x2 matrix in the context.
+    ///
+    /// The matrix is stored in row-major order. This is a [`MapCtx`], so the
+    /// output type can be named.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// // A quarter turn counter-clockwise.
+    /// let rotation = [[0.0, -1.0], [1.0, 0.0]];
+    /// let iter: MapCtx<_, (f64, f64)> = [(1.0, 0.0), (0.0, 2.0)]
+    ///     .into_iter()
+    ///     .with_context(rotation)
+    ///     .transform_with_context();
+    ///
+    /// assert!(iter.eq([(0.0, 1.0), (-2.0, 0.0)]));
+    /// ```
+    fn transform_with_context(self) -> MapCtx<Self, (f64, f64)>
+    where
+        Self: Sized + ContextIterator<Item = (f64, f64), Context = [[f64; 2]; 2]>,
+    {
+        self.map_with_context(|(x, y), [[a, b], [c, d]]| (a * x + b * y, c * x + d * y))
+    }
+
+    /// Yield the accumulator of a left fold after each element.
+    ///
+    /// Each call to `f` receives the accumulator, the element, and the
+    /// context. The first value yielded is `f(init, first, context)`; `init`
+    /// itself is not yielded.
+    fn accumulate_with_context<A>(
+        self,
+        init: A,
+        f: fn(A, Self::Item, &Self::Context) -> A,
+    ) -> AccumulateCtx<Self, A>
+    where
+        Self: Sized,
+        A: Clone,
+    {
+        AccumulateCtx {
+            iter: self,
+            acc: Some(init),
+            f,
+        }
+    }
+
+    /// Yield the running sum of the elements, each multiplied by the context.
+    ///
+    /// The context is read again for each element.
+    fn prefix_sum_with_context(self) -> PrefixSumCtx<Self>
+    where
+        Self: Sized,
+        Self::Item: Add<Output = Self::Item> + Mul<Self::Context, Output = Self::Item> + Clone,
+        Self::Context: Copy,
+    {
+        PrefixSumCtx {
+            iter: self,
+            sum: None,
+        }
+    }
+
+    /// Apply alternating maps to the elements of the iterator.
+    ///
+    /// Elements at even positions, counting from zero, are passed to `even`,
+    /// and the rest to `odd`. This is useful for flattened streams with
+    /// alternating meaning, such as keys and values.
+    fn map_alternating_with_context<O>(
+        self,
+        even: fn(Self::Item, &Self::Context) -> O,
+        odd: fn(Self::Item, &Self::Context) -> O,
+    ) -> MapAlternatingCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        MapAlternatingCtx {
+            iter: self,
+            even,
+            odd,
+            odd_next: false,
+        }
+    }
+
+    /// Replace the key of each key-value pair in the iterator.
+    ///
+    /// The new key is computed from the old key, the value, and the context,
+    /// e.g. to namespace the keys with a prefix from the context. The value is
+    /// moved through unchanged.
+    fn rekey_with_context<K, V, K2>(
+        self,
+        f: fn(&K, &V, &Self::Context) -> K2,
+    ) -> RekeyCtx<Self, K, V, K2>
+    where
+        Self: Sized + ContextIterator<Item = (K, V)>,
+    {
+        RekeyCtx { iter: self, key: f }
+    }
+
+    /// Pair each element with its distance to a pivot stored in the context.
+    ///
+    /// The distance is computed by `dist` from the element and the context,
+    /// e.g. to rank the elements against a reference value.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = [1.0, 4.0, 2.5]
+    ///     .into_iter()
+    ///     .with_context(2.0)
+    ///     .with_distance_to_context(|item: &f64, pivot: &f64| (item - pivot).abs());
+    ///
+    /// assert!(iter.eq([(1.0, 1.0), (4.0, 2.0), (2.5, 0.5)]));
+    /// ```
+    fn with_distance_to_context(
+        self,
+        dist: fn(&Self::Item, &Self::Context) -> f64,
+    ) -> DistanceCtx<Self>
+    where
+        Self: Sized,
+    {
+        DistanceCtx { iter: self, dist }
+    }
+
+    /// Apply one of two maps to each element in the iterator, depending on a
+    /// predicate.
+    ///
+    /// Elements satisfying the predicate are passed to `map`, and the rest to
+    /// `default`.
+    fn map_if_with_context<O>(
+        self,
+        predicate: fn(&Self::Item, &Self::Context) -> bool,
+        map: fn(Self::Item, &Self::Context) -> O,
+        default: fn(Self::Item, &Self::Context) -> O,
+    ) -> MapIfCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        MapIfCtx {
+            iter: self,
+            predicate,
+            map,
+            default,
+        }
+    }
+
+    /// Apply a filter over the elements of the iterator
+    fn filter_with_context(self, filter: fn(&Self::Item, &Self::Context) -> bool) -> FilterCtx<Self>
+    where
+        Self: Sized,
+    {
+        FilterCtx {
+            iter: self,
+            predicate: filter,
+            assume_all: false,
+        }
+    }
+
+    /// Apply a filter over the elements of the iterator, assuming it keeps
+    /// all of them.
+    ///
+    /// This behaves exactly like [`ContextIterator::filter_with_context`],
+    /// except that the lower bound of [`Iterator::size_hint`] is forwarded
+    /// from the inner iterator instead of being zero. It is only a hint for
+    /// preallocation and never changes which elements are yielded.
+    ///
+    /// The caller promises that the predicate does not discard any element.
+    /// Breaking this promise is not unsafe, but results in a `size_hint` that
+    /// does not satisfy the [`Iterator`] contract.
+    fn filter_with_context_assume_all(
+        self,
+        filter: fn(&Self::Item, &Self::Context) -> bool,
+    ) -> FilterCtx<Self>
+    where
+        Self: Sized,
+    {
+        FilterCtx {
+            iter: self,
+            predicate: filter,
+            assume_all: true,
+        }
+    }
+
+    /// Apply a filter over the elements of the iterator
+    fn filter_map_with_context<O>(
+        self,
+        filter: fn(Self::Item, &Self::Context) -> Option<O>,
+    ) -> FilterMapCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        FilterMapCtx {
+            iter: self,
+            predicate: filter,
+        }
+    }
+
+    /// Map the elements of the iterator with a computation that may fail,
+    /// dropping the elements where it does.
+    ///
+    /// This is the same as [`ContextIterator::filter_map_with_context`], named
+    /// for fallible computations such as checked arithmetic.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter: FilterMapCtx<_, u8> = [1, 200, 5, 255]
+    ///     .into_iter()
+    ///     .with_context(100u8)
+    ///     .map_with_context_checked(|item: u8, context: &u8| item.checked_add(*context));
+    ///
+    /// assert!(iter.eq([101, 105]));
+    /// ```
+    fn map_with_context_checked<O>(
+        self,
+        map: fn(Self::Item, &Self::Context) -> Option<O>,
+    ) -> FilterMapCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        self.filter_map_with_context(map)
+    }
+
+    /// Keep each element with a probability read from the context.
+    ///
+    /// The random number generator is seeded with a value read from the
+    /// context when the adaptor is created, so runs with the same seed are
+    /// reproducible. The rate is read again for each element.
+    #[cfg(feature = "rand")]
+    fn sample_with_context(
+        self,
+        rate: fn(&Self::Context) -> f64,
+        seed: fn(&Self::Context) -> u64,
+    ) -> SampleCtx<Self>
+    where
+        Self: Sized,
+    {
+        let seed = seed(self.context());
+        SampleCtx::new(self, rate, seed)
+    }
+
+    /// Filter the elements of the iterator using their index.
+    ///
+    /// The predicate receives the index of the element in the original
+    /// iterator, the element, and the context.
+    fn filter_indexed_with_context(
+        self,
+        filter: fn(usize, &Self::Item, &Self::Context) -> bool,
+    ) -> FilterIndexedCtx<Self>
+    where
+        Self: Sized,
+    {
+        FilterIndexedCtx {
+            iter: self,
+            predicate: filter,
+            index: 0,
+        }
+    }
+
+    /// Yield the elements of the iterator only if the context satisfies a
+    /// predicate.
+    ///
+    /// The predicate is evaluated on the first call to `next`. If it fails,
+    /// the iterator behaves as if it was empty.
+    fn gate_on_context(self, predicate: fn(&Self::Context) -> bool) -> GateCtx<Self>
+    where
+        Self: Sized,
+    {
+        GateCtx {
+            iter: self,
+            predicate,
+            open: None,
+        }
+    }
+
+    /// Yield an element converted from the context before the rest of the
+    /// elements.
+    ///
+    /// The context is cloned and converted with [`From`] on the first call to
+    /// `next`.
+    fn prepend_context(self) -> PrependCtxCtx<Self>
+    where
+        Self: Sized,
+        Self::Item: From<Self::Context>,
+        Self::Context: Clone,
+    {
+        PrependCtxCtx {
+            iter: self,
+            pending: true,
+        }
+    }
+
+    /// Yield the elements followed by a trailer computed from an accumulator.
+    ///
+    /// `step` updates the accumulator with each element and the context, and
+    /// the elements are passed through unchanged. Once the iterator is
+    /// exhausted, `trailer` builds one final element from the accumulator and
+    /// the context, e.g. a checksum or a footer.
+    fn with_trailer<A>(
+        self,
+        init: A,
+        step: fn(A, &Self::Item, &Self::Context) -> A,
+        trailer: fn(A, &Self::Context) -> Self::Item,
+    ) -> TrailerCtx<Self, A>
+    where
+        Self: Sized,
+    {
+        TrailerCtx {
+            iter: self,
+            acc: Some(init),
+            step,
+            trailer,
+        }
+    }
+
+    /// Insert a separator between consecutive elements with different group
+    /// keys.
+    ///
+    /// Each key is computed from an element and the context. Whenever the key
+    /// changes, a separator is built from the previous key, the new key, and
+    /// the context, and yielded before the element that starts the new group.
+    fn group_separators_with_context<K>(
+        self,
+        key: fn(&Self::Item, &Self::Context) -> K,
+        sep: fn(&K, &K, &Self::Context) -> Self::Item,
+    ) -> GroupSepCtx<Self, K>
+    where
+        Self: Sized,
+        K: PartialEq,
+    {
+        GroupSepCtx {
+            iter: self,
+            key,
+            sep,
+            last: None,
+            pending: None,
+        }
+    }
+
+    /// Insert interpolated elements between each pair of consecutive
+    /// elements.
+    ///
+    /// The upsampling factor is read from the context once, when the adaptor
+    /// is created, and `factor - 1` elements are inserted between each pair.
+    /// Each one is built by `lerp` from the two elements of the pair, the
+    /// fractional position between them, and the context. Iterators with
+    /// fewer than two elements are yielded unchanged.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = [0.0, 1.0, 3.0]
+    ///     .into_iter()
+    ///     .with_context(4)
+    ///     .interpolate_with_context(|factor: &usize| *factor, |a: &f64, b, t, _| {
+    ///         a + (b - a) * t
+    ///     });
+    ///
+    /// assert!(iter.eq([0.0, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 2.5, 3.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the factor read from the context is zero.
+    #[allow(clippy::type_complexity)]
+    fn interpolate_with_context(
+        self,
+        factor: fn(&Self::Context) -> usize,
+        lerp: fn(&Self::Item, &Self::Item, f64, &Self::Context) -> Self::Item,
+    ) -> InterpolateCtx<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        let factor = factor(self.context());
+        assert!(
+            factor != 0,
+            "interpolate_with_context: factor must be non-zero"
+        );
+        InterpolateCtx {
+            iter: self,
+            factor,
+            lerp,
+            last: None,
+            next: None,
+            step: 0,
+        }
+    }
+
+    /// Group the elements of the iterator into vectors, with a size read from
+    /// the context.
+    ///
+    /// The size is read again at the start of each chunk. The last chunk may
+    /// be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics during iteration if the size read from the context is zero.
+    #[cfg(feature = "alloc")]
+    fn chunks_from_context(self, size: fn(&Self::Context) -> usize) -> ChunksFromCtxCtx<Self>
+    where
+        Self: Sized,
+    {
+        ChunksFromCtxCtx { iter: self, size }
+    }
+
+    /// Group the elements of the iterator into chunks of `size` elements, and
+    /// reduce each chunk with a function that also receives the context.
+    ///
+    /// This is equivalent to mapping over fixed-size chunks, without creating
+    /// an intermediate iterator of vectors. The last chunk may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[cfg(feature = "alloc")]
+    fn chunk_reduce_with_context<A>(
+        self,
+        size: usize,
+        f: fn(Vec<Self::Item>, &Self::Context) -> A,
+    ) -> ChunkReduceCtx<Self, A>
+    where
+        Self: Sized,
+    {
+        assert!(
+            size != 0,
+            "chunk_reduce_with_context: chunk size must be non-zero"
+        );
+        ChunkReduceCtx {
+            iter: self,
+            size,
+            reduce: f,
+        }
+    }
+
+    /// Group the elements of the iterator into arrays of `N` elements.
+    ///
+    /// A trailing partial chunk is not yielded, and can be recovered with
+    /// [`ArrayChunksCtx::into_remainder`] once the iterator is exhausted.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let mut iter = (0..7).with_context(()).array_chunks_with_context::<3>();
+    ///
+    /// assert_eq!(iter.next(), Some([0, 1, 2]));
+    /// assert_eq!(iter.next(), Some([3, 4, 5]));
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.into_remainder(), vec![6]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[cfg(feature = "alloc")]
+    fn array_chunks_with_context<const N: usize>(self) -> ArrayChunksCtx<Self, N>
+    where
+        Self: Sized,
+        Self::Item: Copy,
+    {
+        assert!(
+            N != 0,
+            "array_chunks_with_context: chunk size must be non-zero"
+        );
+        ArrayChunksCtx {
+            iter: self,
+            remainder: Vec::new(),
+        }
+    }
+
+    /// Recover from the errors in an iterator of results.
+    ///
+    /// `Ok` values are yielded as-is. Each `Err` is passed to `recover` along
+    /// with the context, and the element is dropped if it returns `None`.
+    fn recover_with_context<T, E>(
+        self,
+        recover: fn(E, &Self::Context) -> Option<T>,
+    ) -> RecoverCtx<Self, T, E>
+    where
+        Self: Sized + Iterator<Item = Result<T, E>>,
+    {
+        RecoverCtx {
+            iter: self,
+            recover,
+        }
+    }
+
+    /// Drop every n-th element of the iterator, with `n` read from the
+    /// context.
+    ///
+    /// Positions are counted from one, so `n = 1` drops every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics during iteration if `n` is zero.
+    fn skip_every_nth_with_context(self, n: fn(&Self::Context) -> usize) -> SkipEveryNthCtx<Self>
+    where
+        Self: Sized,
+    {
+        SkipEveryNthCtx {
+            iter: self,
+            n,
+            count: 0,
+        }
+    }
+
+    /// Apply a function to each window of `window` consecutive elements.
+    ///
+    /// The windows overlap, and the function receives the window as a slice
+    /// along with the context. No value is produced if the iterator is shorter
+    /// than the window, unless the partial window is released with
+    /// [`FinalizableContextIterator::with_finalize`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    #[cfg(feature = "alloc")]
+    fn rolling_with_context<A>(
+        self,
+        window: usize,
+        map: fn(&[Self::Item], &Self::Context) -> A,
+    ) -> RollingCtx<Self, A>
+    where
+        Self: Sized,
+    {
+        assert!(window != 0, "rolling_with_context: window must be non-zero");
+        RollingCtx {
+            iter: self,
+            window,
+            map,
+            buffer: alloc::collections::VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Remove consecutive equal elements, keeping the last one of each run.
+    ///
+    /// Equality is decided by `eq`, which also receives the context. Each
+    /// element is compared with the one before it.
+    fn dedup_keep_last_with_context(
+        self,
+        eq: fn(&Self::Item, &Self::Item, &Self::Context) -> bool,
+    ) -> DedupLastCtx<Self>
+    where
+        Self: Sized,
+    {
+        DedupLastCtx {
+            iter: self,
+            eq,
+            last: None,
+        }
+    }
+
+    /// Count the runs of consecutive elements that map to the same key.
+    ///
+    /// Each key is computed from an element and the context. The iterator
+    /// yields each key along with the length of its run, so non-adjacent runs
+    /// with the same key are yielded separately.
+    fn run_length_with_context<K>(
+        self,
+        key: fn(&Self::Item, &Self::Context) -> K,
+    ) -> RunLengthCtx<Self, K>
+    where
+        Self: Sized,
+        K: PartialEq,
+    {
+        RunLengthCtx {
+            iter: self,
+            key,
+            run: None,
+        }
+    }
+
+    /// Merge adjacent elements of the iterator.
+    ///
+    /// The merge function receives the accumulated element, the next one, and
+    /// the context. It returns `Ok` with the merged element, or `Err` with
+    /// both elements unchanged to yield the first one and continue from the
+    /// second.
+    #[allow(clippy::type_complexity)]
+    fn coalesce_with_context(
+        self,
+        merge: fn(
+            Self::Item,
+            Self::Item,
+            &Self::Context,
+        ) -> Result<Self::Item, (Self::Item, Self::Item)>,
+    ) -> CoalesceCtx<Self>
+    where
+        Self: Sized,
+    {
+        CoalesceCtx {
+            iter: self,
+            merge,
+            last: None,
+        }
+    }
+
+    /// Yield elements up to and including the first one matching a sentinel
+    /// predicate.
+    ///
+    /// Unlike [`Iterator::take_while`], the terminating element is yielded,
+    /// as when reading up to and including a delimiter.
+    fn take_until_with_context(
+        self,
+        is_sentinel: fn(&Self::Item, &Self::Context) -> bool,
+    ) -> TakeUntilCtx<Self>
+    where
+        Self: Sized,
+    {
+        TakeUntilCtx {
+            iter: self,
+            is_sentinel,
+            done: false,
+        }
+    }
+
+    /// Pad or truncate the iterator to a length read from the context.
+    ///
+    /// The length is read once, when the adaptor is created. If the iterator
+    /// is shorter, it is padded with elements produced by `fill`; if longer,
+    /// the extra elements are never read.
+    fn resize_with_context(
+        self,
+        len: fn(&Self::Context) -> usize,
+        fill: fn(&Self::Context) -> Self::Item,
+    ) -> ResizeCtx<Self>
+    where
+        Self: Sized,
+    {
+        ResizeCtx {
+            remaining: len(self.context()),
+            iter: self,
+            fill,
+            done: false,
+        }
+    }
+
+    /// Cap the upper bound of the size hint with a value read from the
+    /// context.
+    ///
+    /// This only affects [`Iterator::size_hint`]: the elements are yielded
+    /// unchanged, and none are dropped if the cap turns out to be wrong. It is
+    /// useful when the context knows a bound the iterator does not expose, so
+    /// that collectors can preallocate.
+    fn clamp_size_hint_with_context(self, max: fn(&Self::Context) -> usize) -> ClampHintCtx<Self>
+    where
+        Self: Sized,
+    {
+        ClampHintCtx { iter: self, max }
+    }
+
+    /// Yield elements until the context changes.
+    ///
+    /// The context is cloned on the first call to `next`, and the iteration
+    /// stops once the current context no longer equals that snapshot. This is
+    /// only useful for iterators whose context evolves during the iteration.
+    /// The remaining elements can be recovered with
+    /// [`TakeUntilCtxChangeCtx::into_inner`].
+    fn take_until_context_change(self) -> TakeUntilCtxChangeCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: PartialEq + Clone,
+    {
+        TakeUntilCtxChangeCtx {
+            iter: self,
+            snapshot: None,
+            done: false,
+        }
+    }
+
+    /// Yield the elements of the iterator, each preceded by the context if it
+    /// changed since the previous one.
+    ///
+    /// The first element is always preceded by a [`Annotated::Context`], so
+    /// for an iterator with a constant context the result is the context
+    /// followed by all the elements. This produces a self-describing stream,
+    /// e.g. for serialization.
+    fn annotate_with_context(self) -> AnnotateCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: PartialEq + Clone,
+    {
+        AnnotateCtx {
+            iter: self,
+            last: None,
+            pending: None,
+        }
+    }
+
+    /// Yield at most a number of elements read from the context.
+    ///
+    /// This is [`Iterator::take`] with the limit read from the context, once,
+    /// on the first call to `next`.
+    fn limit_from_context(self, limit: fn(&Self::Context) -> usize) -> LimitCtx<Self>
+    where
+        Self: Sized,
+    {
+        LimitCtx {
+            iter: self,
+            limit,
+            remaining: None,
+        }
+    }
+
+    /// Map each element to an iterable using its index, and flatten the
+    /// result.
+    ///
+    /// The function receives the index of the element in the iterator, the
+    /// element, and the context.
+    fn flat_map_indexed_with_context<U>(
+        self,
+        map: fn(usize, Self::Item, &Self::Context) -> U,
+    ) -> FlatMapIndexedCtx<Self, U>
+    where
+        Self: Sized,
+        U: IntoIterator,
+    {
+        FlatMapIndexedCtx {
+            iter: self,
+            map,
+            index: 0,
+            inner: None,
+        }
+    }
+
+    /// Map each element to a sequence of elements pushed into a scratch
+    /// buffer, and flatten the result.
+    ///
+    /// The function receives the element, the context, and an empty buffer to
+    /// push its outputs into. The buffer is owned by the adaptor and reused
+    /// for every element, so no sub-iterator is allocated per element.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = [1, 2]
+    ///     .into_iter()
+    ///     .with_context(3)
+    ///     .flat_map_scratch_with_context(|item: u32, context: &u32, out: &mut Vec<u32>| {
+    ///         out.extend((0..*context).map(|i| item * 10 + i));
+    ///     });
+    ///
+    /// assert!(iter.eq([10, 11, 12, 20, 21, 22]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn flat_map_scratch_with_context<O>(
+        self,
+        f: fn(Self::Item, &Self::Context, &mut Vec<O>),
+    ) -> FlatMapScratchCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        FlatMapScratchCtx {
+            iter: self,
+            f,
+            scratch: alloc::collections::VecDeque::new(),
+        }
+    }
+
+    /// Map each element to zero, one, or many elements pushed into a small
+    /// inline buffer, and flatten the result.
+    ///
+    /// This covers [`ContextIterator::map_with_context`] (push one element),
+    /// [`ContextIterator::filter_with_context`] (push zero or one), and
+    /// flat-mapping (push many) with a single function. Up to four outputs per
+    /// element are stored inline, without allocating.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = [1, 2, 3]
+    ///     .into_iter()
+    ///     .with_context(2)
+    ///     .expand_with_context(|item: u32, context: &u32, out| {
+    ///         if item != *context {
+    ///             out.push(item);
+    ///             out.push(item * 10);
+    ///         }
+    ///     });
+    ///
+    /// assert!(iter.eq([1, 10, 3, 30]));
+    /// ```
+    #[cfg(feature = "smallvec")]
+    #[allow(clippy::type_complexity)]
+    fn expand_with_context<O>(
+        self,
+        f: fn(Self::Item, &Self::Context, &mut smallvec::SmallVec<[O; 4]>),
+    ) -> ExpandCtx<Self, O>
+    where
+        Self: Sized,
+    {
+        ExpandCtx {
+            iter: self,
+            f,
+            buffer: smallvec::SmallVec::new().into_iter(),
+        }
+    }
+
+    /// Translate the elements using a [`HashMap`](std::collections::HashMap)
+    /// stored in the context.
+    ///
+    /// Each element is mapped to a clone of the value stored for its key, and
+    /// elements whose key is missing are dropped.
+    #[cfg(feature = "std")]
+    fn lookup_with_context<K>(self, key: fn(&Self::Item) -> &K) -> LookupCtx<Self, K>
+    where
+        Self: Sized,
+    {
+        LookupCtx { iter: self, key }
+    }
+
+    /// Report the progress of the iteration after each element.
+    ///
+    /// The report function receives the number of elements yielded so far,
+    /// the upper bound of the remaining elements, and the context. Only the
+    /// elements yielded by this adaptor are counted, so elements dropped by
+    /// an earlier filter are not included.
+    fn progress_with_context(
+        self,
+        report: fn(usize, Option<usize>, &Self::Context),
+    ) -> ProgressCtx<Self>
+    where
+        Self: Sized,
+    {
+        ProgressCtx {
+            iter: self,
+            report,
+            produced: 0,
+        }
+    }
+
+    /// Call a function whenever the context changes between two elements.
+    ///
+    /// Before each element is yielded, its context is compared with the
+    /// context of the previous element, and `f` is called with the old and new
+    /// contexts if they differ. The first element never triggers the call.
+    /// This is only useful for iterators whose context evolves during the
+    /// iteration, e.g. to flush a buffer on a configuration change.
+    fn on_context_change(self, f: fn(&Self::Context, &Self::Context)) -> ContextChangeCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: PartialEq + Clone,
+    {
+        ContextChangeCtx {
+            iter: self,
+            f,
+            last: None,
+        }
+    }
+
+    /// Call hooks for each yielded element and at the end of the iteration.
+    ///
+    /// `on_item` is called with the context after each element, and `on_end`
+    /// with the number of yielded elements and the context the first time
+    /// the iteration ends. The hooks can be used to emit metrics tagged with
+    /// data from the context.
+    fn metered_with_context(
+        self,
+        on_item: fn(&Self::Context),
+        on_end: fn(usize, &Self::Context),
+    ) -> MeteredCtx<Self>
+    where
+        Self: Sized,
+    {
+        MeteredCtx {
+            iter: self,
+            on_item,
+            on_end,
+            count: 0,
+            done: false,
+        }
+    }
+
+    /// Check that the elements are sorted according to a comparison function,
+    /// passing them through unchanged.
+    ///
+    /// The comparison function also receives the context. The check is always
+    /// enabled, and requires cloning each element to compare it with the next.
+    ///
+    /// # Panics
+    ///
+    /// Panics during iteration if an element compares greater than the next.
+    fn assert_sorted_with_context(
+        self,
+        cmp: fn(&Self::Item, &Self::Item, &Self::Context) -> core::cmp::Ordering,
+    ) -> AssertSortedCtx<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        AssertSortedCtx {
+            iter: self,
+            cmp,
+            last: None,
+        }
+    }
+
+    /// Validate each element against the context, yielding the results.
+    ///
+    /// Each element that passes the check is yielded as `Ok`, and each failure
+    /// as the `Err` returned by `check`. Unlike [`Iterator::try_for_each`],
+    /// the iteration continues after an error, so the results can be
+    /// inspected individually or collected into a `Result`.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let results: Vec<_> = [1, 5, 2]
+    ///     .into_iter()
+    ///     .with_context(3)
+    ///     .validate_items_with_context(|item: &u32, max: &u32| match item <= max {
+    ///         true => Ok(()),
+    ///         false => Err(*item),
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(results, [Ok(1), Err(5), Ok(2)]);
+    /// ```
+    fn validate_items_with_context<E>(
+        self,
+        check: fn(&Self::Item, &Self::Context) -> Result<(), E>,
+    ) -> ValidateCtx<Self, E>
+    where
+        Self: Sized,
+    {
+        ValidateCtx { iter: self, check }
+    }
+
+    /// Zip the iterator with another one, panicking if they have different
+    /// lengths.
+    ///
+    /// The context of `self` is forwarded.
+    fn zip_eq_with_context<J>(self, other: J) -> ZipEqCtx<Self, J>
+    where
+        Self: Sized,
+        J: Iterator,
+    {
+        ZipEqCtx { iter: self, other }
+    }
+
+    /// Zip the iterator with another context iterator, checking that their
+    /// contexts are compatible.
+    ///
+    /// The check runs once, lazily, on the first call to `next`. The context
+    /// of `self` is forwarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the first call to `next` if the check fails.
+    fn zip_with_context_check<J>(
+        self,
+        other: J,
+        check: fn(&Self::Context, &J::Context) -> bool,
+    ) -> ZipCheckedCtx<Self, J>
+    where
+        Self: Sized,
+        J: ContextIterator,
+    {
+        ZipCheckedCtx {
+            iter: self,
+            other,
+            check,
+            checked: false,
+        }
+    }
+
+    /// Zip the iterator with another one, until both are exhausted.
+    ///
+    /// Each element is an [`EitherOrBoth`], holding the elements of the
+    /// iterators that have not ended yet. The context of `self` is forwarded.
+    fn zip_longest_with_context<J>(self, other: J) -> ZipLongestCtx<Self, J>
+    where
+        Self: Sized,
+        J: Iterator,
+    {
+        ZipLongestCtx {
+            iter: self,
+            other,
+            iter_done: false,
+            other_done: false,
+        }
+    }
+
+    /// Split the iterator into two independent halves.
+    ///
+    /// Both halves yield the same elements and share a clone of the current
+    /// context. Elements are buffered until both halves have read them; see
+    /// [`TeeCtx`] for the memory cost.
+    #[cfg(feature = "alloc")]
+    fn tee_ctx(self) -> (TeeCtx<Self>, TeeCtx<Self>)
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        Self::Context: Clone,
+    {
+        tee::new(self)
+    }
+
+    /// Remove consecutive elements that map to the same key.
+    ///
+    /// Each key is computed from an element and the context. Only the first
+    /// element of each run of equal keys is yielded.
+    fn dedup_by_key_with_context<K>(
+        self,
+        key: fn(&Self::Item, &Self::Context) -> K,
+    ) -> DedupByKeyCtx<Self, K>
+    where
+        Self: Sized,
+        K: PartialEq,
+    {
+        DedupByKeyCtx {
+            iter: self,
+            key,
+            last: None,
+        }
+    }
+
+    /// Add a counter of the yielded elements to the context.
+    ///
+    /// The new context is a tuple of the counter and a clone of the current
+    /// context. The counter starts at zero and is incremented before each
+    /// element is yielded, so a following [`ContextIterator::map_with_context`]
+    /// sees `1` with the first element.
+    fn numbered(self) -> NumberedCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let context = (0, self.context().clone());
+        NumberedCtx {
+            iter: self,
+            context,
+        }
+    }
+
+    /// Add the upcoming element to the context.
+    ///
+    /// The new context is a tuple of a clone of the current context and the
+    /// element that will be yielded next, or `None` once the last element has
+    /// been yielded. This lets a following [`ContextIterator::map_with_context`]
+    /// look one element ahead. One element is buffered, and the first one is
+    /// read when the adaptor is created.
+    fn with_lookahead(mut self) -> LookaheadCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let next = self.next();
+        let context = (self.context().clone(), next);
+        LookaheadCtx {
+            iter: self,
+            context,
+        }
+    }
+
+    /// Yield every `step`-th element, starting with the first one, and add the
+    /// step size to the context.
+    ///
+    /// The new context is a tuple of a clone of the current context and the
+    /// step, so later stages can compensate for the stride.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    fn step_by_tracked(self, step: usize) -> StepByTrackedCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        assert!(step != 0, "step_by_tracked: step must be non-zero");
+        let context = (self.context().clone(), step);
+        StepByTrackedCtx {
+            iter: self,
+            context,
+            first_take: true,
+        }
+    }
+
+    /// Pair each element with its index and a clone of the context.
+    ///
+    /// This yields the most complete information about each element, which is
+    /// useful for logging or debugging. The context is cloned for every
+    /// element; in hot paths prefer [`ContextIterator::map_with_context`] or
+    /// [`ContextIterator::numbered`], which only borrow it.
+    fn enumerate_with_context_pairs(self) -> EnumeratePairsCtx<Self>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        EnumeratePairsCtx {
+            iter: self,
+            index: 0,
+        }
+    }
+
+    /// Pair each element with the number of elements remaining after it.
+    ///
+    /// The remaining count is only meaningful when iterating from the front,
+    /// so the adaptor does not implement [`DoubleEndedIterator`].
+    fn yield_with_remaining(self) -> WithRemainingCtx<Self>
+    where
+        Self: Sized + ExactSizeIterator,
+    {
+        WithRemainingCtx { iter: self }
+    }
+
+    /// Map each element to a context iterator, and flatten the result.
+    ///
+    /// While a sub-iterator is active its context shadows the outer one, which
+    /// allows modelling hierarchical configurations. See [`FlatMapSubCtx`] for
+    /// the context resolution order.
+    fn flat_map_subcontext<J>(
+        self,
+        map: fn(Self::Item, &Self::Context) -> J,
+    ) -> FlatMapSubCtx<Self, J>
+    where
+        Self: Sized,
+        J: ContextIterator<Context = Self::Context>,
+    {
+        FlatMapSubCtx {
+            iter: self,
+            map,
+            inner: None,
+        }
+    }
+
+    /// Alternate elements from the iterator and another one.
+    ///
+    /// When one of them is exhausted, the remaining elements of the other are
+    /// yielded. The context of `self` is forwarded.
+    fn interleave_with_context<J>(self, other: J) -> InterleaveCtx<Self, J>
+    where
+        Self: Sized,
+        J: Iterator<Item = Self::Item>,
+    {
+        InterleaveCtx {
+            iter: self,
+            other,
+            flag: false,
+            iter_done: false,
+            other_done: false,
+        }
+    }
+
+    /// Replace the iterator with a new one, carrying over a clone of the
+    /// context.
+    ///
+    /// Useful to keep the context across adaptors that only produce plain
+    /// iterators, such as [`Iterator::map`].
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = (0..3).with_context(10);
+    /// let plain = iter.clone().map(|item| item * 2);
+    /// let iter = iter.carry_context(plain);
+    ///
+    /// assert_eq!(iter.context(), &10);
+    /// assert!(iter.eq([0, 2, 4]));
+    /// ```
+    fn carry_context<I2>(self, new_iter: I2) -> WithCtx<I2, Self::Context>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+        I2: Iterator,
+    {
+        new_iter.with_context(self.context().clone())
+    }
+
+    /// Check that two consecutive calls to [`ContextIterator::context`]
+    /// return equal values.
+    ///
+    /// The projection in a [`CtxMap`] is an arbitrary function, so a buggy
+    /// one could return different values each time it is called. This helper
+    /// is meant for tests, and is only available with the `testing` feature.
+    #[cfg(any(test, feature = "testing"))]
+    fn check_context_stable(&self) -> bool
+    where
+        Self::Context: PartialEq + Clone,
+    {
+        let first = self.context().clone();
+        first == *self.context()
+    }
+
+    /// Fold the elements of the iterator, passing the context to each call.
+    ///
+    /// Adaptors over a constant context forward this to the internal iteration
+    /// of their inner iterator, which is usually faster than repeatedly
+    /// calling `next`.
+    fn fold_with_context<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        Self: Sized,
+        F: FnMut(B, Self::Item, &Self::Context) -> B,
+    {
+        let mut acc = init;
+        while let Some(item) = self.next() {
+            acc = f(acc, item, self.context());
+        }
+        acc
+    }
+
+    /// Fold the elements of the iterator into two accumulators at once,
+    /// passing the context to each call.
+    ///
+    /// This is [`ContextIterator::fold_with_context`] with a pair of
+    /// accumulators, to compute two results in a single pass.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let (sum, count) = [1.0, 2.0, 6.0]
+    ///     .into_iter()
+    ///     .with_context(0.5)
+    ///     .fold2_with_context(0.0, 0, |sum, count, item: f64, weight: &f64| {
+    ///         (sum + item * weight, count + 1)
+    ///     });
+    ///
+    /// assert_eq!((sum, count), (4.5, 3));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn fold2_with_context<A, B>(
+        self,
+        init_a: A,
+        init_b: B,
+        f: fn(A, B, Self::Item, &Self::Context) -> (A, B),
+    ) -> (A, B)
+    where
+        Self: Sized,
+    {
+        self.fold_with_context((init_a, init_b), |(a, b), item, context| {
+            f(a, b, item, context)
+        })
+    }
+
+    /// Fold the elements of the iterator both front to back and back to
+    /// front, passing the context to each call.
+    ///
+    /// Returns the results of the `forward` and `backward` folds, both
+    /// starting from `init`. The iterator is cloned before the first pass, and
+    /// the clone is consumed from the back for the second one, so elements
+    /// are produced twice.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let (forward, backward) = ["a", "b", "c"]
+    ///     .into_iter()
+    ///     .with_context("-")
+    ///     .bidirectional_fold_with_context(
+    ///         String::new(),
+    ///         |acc, item: &str, sep: &&str| acc + item + sep,
+    ///         |acc, item: &str, sep: &&str| acc + item + sep,
+    ///     );
+    ///
+    /// assert_eq!(forward, "a-b-c-");
+    /// assert_eq!(backward, "c-b-a-");
+    /// ```
+    fn bidirectional_fold_with_context<B>(
+        self,
+        init: B,
+        forward: fn(B, Self::Item, &Self::Context) -> B,
+        backward: fn(B, Self::Item, &Self::Context) -> B,
+    ) -> (B, B)
+    where
+        Self: Sized + DoubleEndedIterator + Clone,
+        B: Clone,
+    {
+        let mut back = self.clone();
+        let forward = self.fold_with_context(init.clone(), forward);
+        let mut acc = init;
+        while let Some(item) = back.next_back() {
+            acc = backward(acc, item, back.context());
+        }
+        (forward, acc)
+    }
+
+    /// Reduce the elements of the iterator with a fallible function, passing
+    /// the context to each call.
+    ///
+    /// Stops at the first error, which is returned instead. Returns `Ok(None)`
+    /// if the iterator is empty.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let sum = [1u8, 2, 3]
+    ///     .into_iter()
+    ///     .with_context(10u8)
+    ///     .try_reduce_with_context(|a, b, max: &u8| match a + b {
+    ///         sum if sum <= *max => Ok(sum),
+    ///         _ => Err("overflow"),
+    ///     });
+    /// assert_eq!(sum, Ok(Some(6)));
+    ///
+    /// let sum = [5u8, 6]
+    ///     .into_iter()
+    ///     .with_context(10u8)
+    ///     .try_reduce_with_context(|a, b, max: &u8| match a + b {
+    ///         sum if sum <= *max => Ok(sum),
+    ///         _ => Err("overflow"),
+    ///     });
+    /// assert_eq!(sum, Err("overflow"));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn try_reduce_with_context<E>(
+        mut self,
+        f: fn(Self::Item, Self::Item, &Self::Context) -> Result<Self::Item, E>,
+    ) -> Result<Option<Self::Item>, E>
+    where
+        Self: Sized,
+    {
+        let mut acc = match self.next() {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+        while let Some(item) = self.next() {
+            acc = f(acc, item, self.context())?;
+        }
+        Ok(Some(acc))
+    }
+
+    /// Take the next element along with a clone of the context.
+    ///
+    /// Returning a reference to the context would keep the iterator borrowed,
+    /// so the context is cloned instead. The rest of the iterator is left in
+    /// place. Returns `None` if the iterator is exhausted.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let mut iter = ["id,name", "1,one", "2,two"].into_iter().with_context(',');
+    ///
+    /// let (header, separator) = iter.first_with_context().unwrap();
+    /// assert_eq!(header.split(separator).count(), 2);
+    /// assert!(iter.eq(["1,one", "2,two"]));
+    /// ```
+    fn first_with_context(&mut self) -> Option<(Self::Item, Self::Context)>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let first = self.next()?;
+        Some((first, self.context().clone()))
+    }
+
+    /// Split the first element from the rest of the iterator.
+    ///
+    /// The rest of the iterator is wrapped in a [`WithCtx`] carrying a clone
+    /// of the context. Returns `None` if the iterator is empty.
+    #[allow(clippy::type_complexity)]
+    fn split_first_with_context(mut self) -> Option<(Self::Item, WithCtx<Self, Self::Context>)>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let first = self.next()?;
+        let context = self.context().clone();
+        Some((first, self.with_context(context)))
+    }
+
+    /// Split the elements into the ones retained by a predicate and the ones
+    /// removed by it.
+    ///
+    /// Returns `(retained, removed)`. This is equivalent to
+    /// [`Iterator::partition`] with a predicate that has access to the
+    /// context.
+    fn drain_filter_with_context<C>(
+        mut self,
+        retain: fn(&Self::Item, &Self::Context) -> bool,
+    ) -> (C, C)
+    where
+        Self: Sized,
+        C: Default + Extend<Self::Item>,
+    {
+        let mut retained = C::default();
+        let mut removed = C::default();
+        while let Some(item) = self.next() {
+            match retain(&item, self.context()) {
+                true => retained.extend(Some(item)),
+                false => removed.extend(Some(item)),
+            }
+        }
+        (retained, removed)
+    }
+
+    /// Drive the iterator to the end, returning the number of elements and a
+    /// clone of the final context.
+    ///
+    /// See [`WithCtx::consume`] to move the context out instead of cloning
+    /// it.
+    fn consume_with_context(mut self) -> (usize, Self::Context)
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let count = self.by_ref().count();
+        (count, self.context().clone())
+    }
 
-    /// Get the context.
-    fn context_map<F, O>(self, map: F) -> CtxMap<Self, F>
+    /// Assert that the iterator yields the expected elements.
+    ///
+    /// Meant for tests. On a mismatch the panic message reports the index of
+    /// the first divergence and the context at that point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element differs from the expected one, or if the iterator
+    /// yields more or fewer elements than expected.
+    #[track_caller]
+    fn assert_eq_with_context(mut self, expected: &[Self::Item])
     where
         Self: Sized,
-        F: Fn(&Self::Context) -> &O,
+        Self::Item: PartialEq + Debug,
+        Self::Context: Debug,
     {
-        CtxMap { iter: self, map }
+        let mut index = 0;
+        while let Some(item) = self.next() {
+            match expected.get(index) {
+                Some(expected) if *expected == item => {}
+                Some(expected) => panic!(
+                    "assert_eq_with_context: mismatch at index {index}: expected {expected:?}, found {item:?}\ncontext: {:?}",
+                    self.context()
+                ),
+                None => panic!(
+                    "assert_eq_with_context: unexpected element at index {index}: {item:?}\ncontext: {:?}",
+                    self.context()
+                ),
+            }
+            index += 1;
+        }
+        if index < expected.len() {
+            panic!(
+                "assert_eq_with_context: iterator ended at index {index}, expected {} elements\ncontext: {:?}",
+                expected.len(),
+                self.context()
+            );
+        }
     }
 
-    /// Apply a map to each element in the iterator.
-    fn map_with_context<O>(self, map: fn(Self::Item, &Self::Context) -> O) -> MapCtx<Self, O>
+    /// Count the number of distinct keys derived from the elements of the
+    /// iterator.
+    ///
+    /// Each key is computed from an element and the context. The keys are
+    /// stored in a [`HashSet`], which allocates space for every distinct key
+    /// seen.
+    #[cfg(feature = "std")]
+    fn count_unique_with_context<K>(mut self, key: fn(&Self::Item, &Self::Context) -> K) -> usize
     where
         Self: Sized,
+        K: Eq + Hash,
     {
-        MapCtx { iter: self, map }
+        let mut seen = HashSet::new();
+        while let Some(item) = self.next() {
+            seen.insert(key(&item, self.context()));
+        }
+        seen.len()
     }
 
-    /// Apply a filter over the elements of the iterator
-    fn filter_with_context(self, filter: fn(&Self::Item, &Self::Context) -> bool) -> FilterCtx<Self>
+    /// Return the `k` largest elements according to a comparison function,
+    /// sorted in descending order.
+    ///
+    /// The comparison function also receives the context. The elements are
+    /// selected with a bounded heap, in `O(n log k)` time and `O(k)` memory,
+    /// so this is cheaper than sorting all the elements when `k` is small. If
+    /// the iterator has fewer than `k` elements, all of them are returned. The
+    /// order of elements that compare as equal is unspecified.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let top = [(1, 5), (2, 1), (3, 7), (4, 3)]
+    ///     .into_iter()
+    ///     .with_context(2)
+    ///     .top_k_with_context(2, |a: &(u32, u32), b, weight: &u32| {
+    ///         (a.0 + weight * a.1).cmp(&(b.0 + weight * b.1))
+    ///     });
+    ///
+    /// assert!(top.eq([(3, 7), (1, 5)]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn top_k_with_context(
+        mut self,
+        k: usize,
+        cmp: fn(&Self::Item, &Self::Item, &Self::Context) -> core::cmp::Ordering,
+    ) -> vec::IntoIter<Self::Item>
     where
         Self: Sized,
     {
-        FilterCtx {
-            iter: self,
-            predicate: filter,
+        let mut heap = Vec::with_capacity(k.min(self.size_hint().0));
+        if k == 0 {
+            return heap.into_iter();
+        }
+        while let Some(item) = self.next() {
+            let context = self.context();
+            let cmp = |a: &Self::Item, b: &Self::Item| cmp(a, b, context);
+            if heap.len() < k {
+                let pos = heap.len();
+                heap.push(item);
+                select::sift_up(&mut heap, pos, cmp);
+            } else if cmp(&item, &heap[0]).is_gt() {
+                heap[0] = item;
+                select::sift_down(&mut heap, 0, cmp);
+            }
         }
+        let context = self.context();
+        heap.sort_by(|a, b| cmp(b, a, context));
+        heap.into_iter()
     }
 
-    /// Apply a filter over the elements of the iterator
-    fn filter_map_with_context<O>(
+    /// Sort a nearly sorted iterator, using a sliding window of `window`
+    /// elements.
+    ///
+    /// The window is kept in a heap, and each call to `next` yields its least
+    /// element according to a comparison function that also receives the
+    /// context. The output is sorted if no element is more than `window - 1`
+    /// positions after its sorted position. The remaining elements are flushed
+    /// in order at the end.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = [2, 1, 3, 5, 4, 6]
+    ///     .into_iter()
+    ///     .with_context(())
+    ///     .sort_within_window_with_context(2, |a: &u32, b, _| a.cmp(b));
+    ///
+    /// assert!(iter.eq([1, 2, 3, 4, 5, 6]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    #[cfg(feature = "alloc")]
+    fn sort_within_window_with_context(
         self,
-        filter: fn(Self::Item, &Self::Context) -> Option<O>,
-    ) -> FilterMapCtx<Self, O>
+        window: usize,
+        cmp: fn(&Self::Item, &Self::Item, &Self::Context) -> core::cmp::Ordering,
+    ) -> SortWindowCtx<Self>
     where
         Self: Sized,
     {
-        FilterMapCtx {
+        assert!(
+            window != 0,
+            "sort_within_window_with_context: window must be non-zero"
+        );
+        SortWindowCtx {
+            heap: Vec::with_capacity(window.min(self.size_hint().0)),
             iter: self,
-            predicate: filter,
+            window,
+            cmp,
+        }
+    }
+
+    /// Format the elements of the iterator into a string, separated by a
+    /// string read from the context.
+    ///
+    /// The separator is read once, before the first element.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let line = [1, 2, 3]
+    ///     .into_iter()
+    ///     .with_context(("\t", 3))
+    ///     .join_with_context(|(separator, _): &(&str, usize)| separator);
+    ///
+    /// assert_eq!(line, "1\t2\t3");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn join_with_context(mut self, sep: fn(&Self::Context) -> &str) -> String
+    where
+        Self: Sized,
+        Self::Item: core::fmt::Display,
+    {
+        use core::fmt::Write;
+
+        let sep = String::from(sep(self.context()));
+        let mut joined = String::new();
+        if let Some(first) = self.next() {
+            write!(joined, "{first}").unwrap();
+            for item in self {
+                joined.push_str(&sep);
+                write!(joined, "{item}").unwrap();
+            }
+        }
+        joined
+    }
+
+    /// Collect each element paired with a clone of the context at the time it
+    /// was yielded.
+    ///
+    /// This eagerly consumes the iterator and allocates a vector with all the
+    /// pairs. The result is a plain iterator that can be passed to code
+    /// unaware of contexts.
+    #[cfg(feature = "alloc")]
+    fn into_paired(mut self) -> vec::IntoIter<(Self::Item, Self::Context)>
+    where
+        Self: Sized,
+        Self::Context: Clone,
+    {
+        let mut pairs = Vec::with_capacity(self.size_hint().0);
+        while let Some(item) = self.next() {
+            pairs.push((item, self.context().clone()));
         }
+        pairs.into_iter()
+    }
+
+    /// Collect an iterator of results, returning the collection along with a
+    /// clone of the context.
+    ///
+    /// Stops at the first error, which is returned instead.
+    fn try_collect_with_context<C, T, E>(mut self) -> Result<(C, Self::Context), E>
+    where
+        Self: Sized + Iterator<Item = Result<T, E>>,
+        Self::Context: Clone,
+        C: FromIterator<T>,
+    {
+        let collection = self.by_ref().collect::<Result<C, E>>()?;
+        Ok((collection, self.context().clone()))
     }
 }
 
@@ -112,6 +2179,131 @@ pub struct WithCtx<I, Ctx> {
     pub(self) context: Ctx,
 }
 
+impl<I, Ctx> WithCtx<I, Ctx>
+where
+    I: Iterator,
+{
+    /// Fold the elements of the iterator, threading the context by value
+    /// alongside the accumulator.
+    ///
+    /// Each call to `f` receives the accumulator, the element, and the current
+    /// context, and returns the new accumulator and context. Returns the final
+    /// accumulator and context.
+    pub fn fold_context<B>(self, init: B, f: fn(B, I::Item, Ctx) -> (B, Ctx)) -> (B, Ctx) {
+        let WithCtx { iter, context } = self;
+        iter.fold((init, context), |(acc, context), item| {
+            f(acc, item, context)
+        })
+    }
+
+    /// Drive the iterator to the end, returning the number of elements and
+    /// the context.
+    pub fn consume(self) -> (usize, Ctx) {
+        (self.iter.count(), self.context)
+    }
+
+    /// Update the context after each yielded element.
+    ///
+    /// The first element sees the initial context, and each following element
+    /// sees the context updated once more. See [`UpdateCtx`] for details on
+    /// when the update is applied.
+    pub fn update_context_each(self, update: fn(&mut Ctx)) -> UpdateCtx<I, Ctx> {
+        UpdateCtx {
+            iter: self.iter,
+            context: self.context,
+            update,
+            pending: false,
+        }
+    }
+
+    /// Convert the context into another type with [`TryFrom`].
+    ///
+    /// Returns the conversion error if it fails, in which case the iterator is
+    /// dropped.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// let iter = (0..3).with_context(300u32);
+    /// assert!(iter.try_map_context::<u8>().is_err());
+    /// ```
+    pub fn try_map_context<O>(self) -> Result<WithCtx<I, O>, O::Error>
+    where
+        O: TryFrom<Ctx>,
+    {
+        Ok(WithCtx {
+            iter: self.iter,
+            context: O::try_from(self.context)?,
+        })
+    }
+
+    /// Scan the elements of the iterator, using the context as the state.
+    ///
+    /// Like [`Iterator::scan`], `f` mutates the state and produces each output
+    /// element, or `None` to stop the iteration. The context is no longer
+    /// constant after this adaptor, and reflects the evolved state.
+    pub fn scan_context<O>(
+        self,
+        f: fn(&mut Ctx, I::Item) -> Option<O>,
+    ) -> ScanContextCtx<I, Ctx, O> {
+        ScanContextCtx {
+            iter: self.iter,
+            context: self.context,
+            f,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, K, S> WithCtx<I, std::collections::HashMap<K, usize, S>>
+where
+    I: Iterator,
+{
+    /// Count the occurrences of each key into the map stored in the context.
+    ///
+    /// The key of each element is computed by `key`. The elements are yielded
+    /// unchanged, and [`TallyCtx::into_tally`] drives the iterator to the end
+    /// and returns the filled map.
+    pub fn tally_into_context(self, key: fn(&I::Item) -> K) -> TallyCtx<I, K, S> {
+        TallyCtx {
+            iter: self.iter,
+            context: self.context,
+            key,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, K, S> WithCtx<I, HashSet<K, S>>
+where
+    I: Iterator,
+{
+    /// Yield only the first element for each key, using the set stored in the
+    /// context to track the keys seen so far.
+    ///
+    /// Unlike [`ContextIterator::dedup_by_key_with_context`], which only
+    /// removes consecutive duplicates, this removes duplicates across the
+    /// whole iterator. Every distinct key is kept in the set, so memory grows
+    /// with the number of distinct keys.
+    ///
+    /// ```
+    /// # use context_iterators::*;
+    /// # use std::collections::HashSet;
+    /// let iter = [3, 1, 3, 2, 1]
+    ///     .into_iter()
+    ///     .with_context(HashSet::new())
+    ///     .unique_with_context(|item: &u32| *item);
+    ///
+    /// assert!(iter.eq([3, 1, 2]));
+    /// ```
+    pub fn unique_with_context(self, key: fn(&I::Item) -> K) -> UniqueCtx<I, K, S> {
+        UniqueCtx {
+            iter: self.iter,
+            context: self.context,
+            key,
+        }
+    }
+}
+
 impl<I, Ctx> Iterator for WithCtx<I, Ctx>
 where
     I: Iterator,
@@ -127,7 +2319,14 @@ where
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, self.iter.size_hint().1)
+        self.iter.size_hint()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(init, f)
     }
 }
 
@@ -140,6 +2339,18 @@ where
     fn context(&self) -> &Self::Context {
         &self.context
     }
+
+    fn context_is_constant(&self) -> bool {
+        true
+    }
+
+    fn fold_with_context<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item, &Self::Context) -> B,
+    {
+        let context = self.context;
+        self.iter.fold(init, |acc, item| f(acc, item, &context))
+    }
 }
 
 impl<I, Ctx> DoubleEndedIterator for WithCtx<I, Ctx>
@@ -149,6 +2360,10 @@ where
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.iter.nth_back(n)
+    }
 }
 
 impl<I, Ctx> ExactSizeIterator for WithCtx<I, Ctx>
@@ -162,6 +2377,29 @@ where
 
 impl<I, Ctx> FusedIterator for WithCtx<I, Ctx> where I: FusedIterator {}
 
+/// A snapshot of the context of an iterator.
+///
+/// Created by [`ContextIterator::checkpoint`].
+#[derive(Clone, Debug)]
+pub struct Checkpoint<Ctx> {
+    pub(self) context: Ctx,
+}
+
+impl<Ctx> Checkpoint<Ctx> {
+    /// Get the saved context.
+    pub fn context(&self) -> &Ctx {
+        &self.context
+    }
+
+    /// Replace the context of an iterator with the saved one.
+    pub fn restore_context<I, C>(self, iter: WithCtx<I, C>) -> WithCtx<I, Ctx> {
+        WithCtx {
+            iter: iter.iter,
+            context: self.context,
+        }
+    }
+}
+
 /// Apply a function to the context of an iterator.
 #[derive(Clone, Debug)]
 pub struct CtxMap<I, F> {
@@ -186,6 +2424,13 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
     }
+
+    fn fold<B, G>(self, init: B, f: G) -> B
+    where
+        G: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(init, f)
+    }
 }
 
 impl<I, F, O> ContextIterator for CtxMap<I, F>
@@ -198,6 +2443,23 @@ where
     fn context(&self) -> &O {
         (self.map)(self.iter.context())
     }
+
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth() + 1
+    }
+
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+
+    fn fold_with_context<B, G>(self, init: B, mut f: G) -> B
+    where
+        G: FnMut(B, Self::Item, &Self::Context) -> B,
+    {
+        let map = self.map;
+        self.iter
+            .fold_with_context(init, |acc, item, context| f(acc, item, map(context)))
+    }
 }
 
 impl<I, F> DoubleEndedIterator for CtxMap<I, F>
@@ -207,6 +2469,10 @@ where
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.iter.nth_back(n)
+    }
 }
 
 impl<I, F> ExactSizeIterator for CtxMap<I, F>
@@ -252,6 +2518,15 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
     }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let map = self.map;
+        self.iter
+            .fold_with_context(init, |acc, item, context| f(acc, map(item, context)))
+    }
 }
 
 impl<I, O> DoubleEndedIterator for MapCtx<I, O>
@@ -263,6 +2538,12 @@ where
             .next_back()
             .map(|item| (self.map)(item, self.iter.context()))
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.iter
+            .nth_back(n)
+            .map(|item| (self.map)(item, self.iter.context()))
+    }
 }
 
 impl<I, O> ExactSizeIterator for MapCtx<I, O>
@@ -285,6 +2566,26 @@ where
     fn context(&self) -> &Self::Context {
         self.iter.context()
     }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+
+    fn fold_with_context<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item, &Self::Context) -> B,
+    {
+        let map = self.map;
+        self.iter.fold_with_context(init, |acc, item, context| {
+            f(acc, map(item, context), context)
+        })
+    }
 }
 
 /// Filter the elements of an iterator, passing a context to each
@@ -302,6 +2603,8 @@ where
 {
     pub(self) iter: I,
     pub(self) predicate: fn(&I::Item, &I::Context) -> bool,
+    /// Whether the predicate is assumed to keep every element.
+    pub(self) assume_all: bool,
 }
 
 impl<I> Iterator for FilterCtx<I>
@@ -324,7 +2627,11 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, self.iter.size_hint().1)
+        let (lower, upper) = self.iter.size_hint();
+        match self.assume_all {
+            true => (lower, upper),
+            false => (0, upper),
+        }
     }
 
     #[inline]
@@ -364,6 +2671,16 @@ where
     fn context(&self) -> &Self::Context {
         self.iter.context()
     }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
 }
 
 /// Map a function over the elements of an iterator, simultaneously filtering elements.
@@ -443,6 +2760,16 @@ where
     fn context(&self) -> &Self::Context {
         self.iter.context()
     }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
 }
 
 #[cfg(test)]
@@ -484,4 +2811,268 @@ mod test {
         assert_eq!(iter.len(), 10);
         assert!(iter.eq(42..52));
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn count_unique() {
+        let count = (0..10)
+            .with_context(3)
+            .count_unique_with_context(|item: &usize, context: &usize| item / context);
+
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn into_paired() {
+        let iter = (0..3).with_context(42).into_paired();
+
+        assert!(iter.eq([(0, 42), (1, 42), (2, 42)]));
+    }
+
+    #[test]
+    fn try_collect() {
+        let result: Result<(Vec<_>, _), ()> = [Ok(1), Ok(2)]
+            .into_iter()
+            .with_context(42)
+            .try_collect_with_context();
+        assert_eq!(result, Ok((vec![1, 2], 42)));
+
+        let result: Result<(Vec<_>, _), &str> = [Ok(1), Err("fail"), Ok(3)]
+            .into_iter()
+            .with_context(42)
+            .try_collect_with_context();
+        assert_eq!(result, Err("fail"));
+    }
+
+    #[test]
+    fn nth_back() {
+        let mut iter = (0..100)
+            .with_context(0)
+            .map_with_context(|item: usize, _| item);
+
+        assert_eq!(iter.nth_back(5), Some(94));
+        assert_eq!(iter.next_back(), Some(93));
+    }
+
+    #[test]
+    fn context_chain_depth() {
+        let iter = (0..3).with_context((1, (2, 3)));
+        assert_eq!(iter.context_chain_depth(), 0);
+
+        let iter = iter
+            .context_map(|context: &(usize, (usize, usize))| &context.1)
+            .map_with_context(|item: usize, _| item)
+            .context_map(|context: &(usize, usize)| &context.0);
+        assert_eq!(iter.context(), &2);
+        assert_eq!(iter.context_chain_depth(), 2);
+    }
+
+    #[test]
+    fn with_context_from_first() {
+        let iter = (3..6).with_context_from_first(|first: &usize| first * 2);
+        let iter = iter.unwrap();
+        assert_eq!(iter.context(), &6);
+        assert!(iter.eq(4..6));
+
+        assert!((0..0).with_context_from_first(|_: &usize| ()).is_none());
+    }
+
+    #[test]
+    fn carry_context() {
+        let iter = (0..3).with_context(42);
+        let mapped = iter.clone().map(|item| item + 1).recontextualize(7);
+        assert_eq!(mapped.context(), &7);
+
+        let iter = iter.carry_context(mapped);
+        assert_eq!(iter.context(), &42);
+        assert!(iter.eq(1..4));
+    }
+
+    #[test]
+    fn filter_assume_all() {
+        let iter = [1, 2, 3]
+            .into_iter()
+            .with_context(0)
+            .filter_with_context_assume_all(|item: &usize, context: &usize| item > context);
+
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert!(iter.eq([1, 2, 3]));
+    }
+
+    #[test]
+    fn context_stable() {
+        let iter = (0..3)
+            .with_context((1, 2))
+            .context_map(|context: &(usize, usize)| &context.1);
+
+        assert!(iter.check_context_stable());
+    }
+
+    #[test]
+    fn fold_context() {
+        let (sum, context) = (1..4)
+            .with_context(1)
+            .fold_context(0, |acc, item: usize, scale: usize| {
+                (acc + item * scale, scale * 2)
+            });
+
+        assert_eq!(sum, 1 + 2 * 2 + 3 * 4);
+        assert_eq!(context, 8);
+    }
+
+    #[test]
+    fn fold_with_context() {
+        let iter = (0..4)
+            .with_context((1, 10))
+            .context_map(|context: &(usize, usize)| &context.1)
+            .map_with_context(|item: usize, context: &usize| item * context);
+
+        let sum = iter
+            .clone()
+            .fold_with_context(0, |acc, item, context| acc + item + context);
+        assert_eq!(sum, 60 + 40);
+        assert_eq!(iter.sum::<usize>(), 60);
+    }
+
+    #[test]
+    fn consume() {
+        let iter = (0..4)
+            .with_context(42)
+            .filter_with_context(|item: &usize, _| item % 2 == 0);
+        assert_eq!(iter.consume_with_context(), (2, 42));
+
+        assert_eq!((0..4).with_context(42).consume(), (4, 42));
+    }
+
+    #[test]
+    fn assert_eq_with_context() {
+        (0..3).with_context(42).assert_eq_with_context(&[0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatch at index 1: expected 3, found 1\ncontext: 42")]
+    fn assert_eq_with_context_mismatch() {
+        (0..3).with_context(42).assert_eq_with_context(&[0, 3, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "iterator ended at index 3")]
+    fn assert_eq_with_context_short() {
+        (0..3)
+            .with_context(42)
+            .assert_eq_with_context(&[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn split_first() {
+        let (first, rest) = (0..3).with_context(42).split_first_with_context().unwrap();
+        assert_eq!(first, 0);
+        assert_eq!(rest.context(), &42);
+        assert!(rest.eq(1..3));
+
+        assert!((0..0).with_context(42).split_first_with_context().is_none());
+    }
+
+    #[test]
+    fn drain_filter() {
+        let (retained, removed): (Vec<_>, Vec<_>) = (0..6)
+            .with_context(3)
+            .drain_filter_with_context(|item: &usize, context: &usize| item < context);
+
+        assert_eq!(retained, [0, 1, 2]);
+        assert_eq!(removed, [3, 4, 5]);
+    }
+
+    #[test]
+    fn try_context_map() {
+        let validate = |context: &Option<u32>| context.map(drop).ok_or("missing");
+        fn project(context: &Option<u32>) -> &u32 {
+            context.as_ref().unwrap()
+        }
+
+        let iter = (0..3).with_context(Some(7));
+        let iter = iter.try_context_map(validate, project).unwrap();
+        assert_eq!(iter.context(), &7);
+
+        let iter = (0..3).with_context(None);
+        assert_eq!(
+            iter.try_context_map(validate, project).err(),
+            Some("missing")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn from_vec_len() {
+        let iter = from_vec_len_context(vec![1, 2, 3, 4, 5])
+            .filter_with_context(|item: &usize, len: &usize| item * 2 > *len);
+
+        assert_eq!(iter.context(), &5);
+        assert!(iter.eq([3, 4, 5]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn top_k() {
+        let cmp = |a: &i32, b: &i32, center: &i32| (a - center).abs().cmp(&(b - center).abs());
+        let top = [1, 10, 4, 6, 2, 7, 5].into_iter().with_context(5);
+        assert!(top.top_k_with_context(3, cmp).eq([10, 1, 2]));
+
+        let top = [4, 1].into_iter().with_context(5);
+        assert!(top.top_k_with_context(3, cmp).eq([1, 4]));
+
+        let top = [4, 1].into_iter().with_context(5);
+        assert_eq!(top.top_k_with_context(0, cmp).len(), 0);
+    }
+
+    #[test]
+    fn try_map_context() {
+        /// A percentage, between 0 and 100.
+        #[derive(Debug, PartialEq)]
+        struct Percent(u8);
+
+        impl TryFrom<u32> for Percent {
+            type Error = u32;
+
+            fn try_from(value: u32) -> Result<Self, u32> {
+                match value {
+                    0..=100 => Ok(Percent(value as u8)),
+                    _ => Err(value),
+                }
+            }
+        }
+
+        let iter = (0..3)
+            .with_context(42)
+            .try_map_context::<Percent>()
+            .unwrap();
+        assert_eq!(iter.context(), &Percent(42));
+        assert!(iter.eq(0..3));
+
+        let iter = (0..3).with_context(142);
+        assert_eq!(iter.try_map_context::<Percent>().err(), Some(142));
+    }
+
+    #[test]
+    fn checkpoint() {
+        let (checkpoint, mut iter) = (0..3).with_context(1).checkpoint();
+        assert_eq!(iter.next(), Some(0));
+
+        let iter = checkpoint.restore_context((5..7).with_context("other"));
+        assert_eq!(iter.context(), &1);
+        assert!(iter.eq(5..7));
+    }
+
+    #[test]
+    fn context_is_constant() {
+        let iter = (0..3)
+            .with_context((1, 2))
+            .context_map(|context: &(usize, usize)| &context.0)
+            .filter_with_context(|item: &usize, context: &usize| item > context);
+        assert!(iter.context_is_constant());
+
+        let iter = (0..3).with_context(1).update_context_each(|c| *c += 1);
+        assert!(!iter.context_is_constant());
+    }
 }
@@ -0,0 +1,388 @@
+//! Variations of the context-aware map adaptor.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Map one of two functions over each element in the iterator, chosen by a
+/// predicate.
+///
+/// Created by [`ContextIterator::map_if_with_context`]. Each function call is
+/// passed the context of the iterator along with the element.
+#[derive(Clone, Debug)]
+pub struct MapIfCtx<I, O>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) predicate: fn(&I::Item, &I::Context) -> bool,
+    pub(crate) map: fn(I::Item, &I::Context) -> O,
+    pub(crate) default: fn(I::Item, &I::Context) -> O,
+}
+
+impl<I, O> MapIfCtx<I, O>
+where
+    I: ContextIterator,
+{
+    #[inline]
+    fn apply(&self, item: I::Item) -> O {
+        let context = self.iter.context();
+        if (self.predicate)(&item, context) {
+            (self.map)(item, context)
+        } else {
+            (self.default)(item, context)
+        }
+    }
+}
+
+impl<I, O> Iterator for MapIfCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some(self.apply(item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, O> DoubleEndedIterator for MapIfCtx<I, O>
+where
+    I: DoubleEndedIterator + ContextIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next_back()?;
+        Some(self.apply(item))
+    }
+}
+
+impl<I, O> ExactSizeIterator for MapIfCtx<I, O>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, O> FusedIterator for MapIfCtx<I, O> where I: FusedIterator + ContextIterator {}
+
+impl<I, O> ContextIterator for MapIfCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Map alternating functions over the elements of an iterator.
+///
+/// Created by [`ContextIterator::map_alternating_with_context`]. Positions
+/// are counted from zero, so the first element is mapped by the `even`
+/// function. Each function call is passed the context of the iterator along
+/// with the element.
+#[derive(Clone, Debug)]
+pub struct MapAlternatingCtx<I, O>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) even: fn(I::Item, &I::Context) -> O,
+    pub(crate) odd: fn(I::Item, &I::Context) -> O,
+    pub(crate) odd_next: bool,
+}
+
+impl<I, O> Iterator for MapAlternatingCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let map = match self.odd_next {
+            true => self.odd,
+            false => self.even,
+        };
+        self.odd_next = !self.odd_next;
+        Some(map(item, self.iter.context()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, O> ExactSizeIterator for MapAlternatingCtx<I, O>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, O> FusedIterator for MapAlternatingCtx<I, O> where I: FusedIterator + ContextIterator {}
+
+impl<I, O> ContextIterator for MapAlternatingCtx<I, O>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Replace the key of each key-value pair in the iterator.
+///
+/// Created by [`ContextIterator::rekey_with_context`]. The new key is computed
+/// from the old key, the value, and the context. The value is moved through
+/// unchanged.
+#[derive(Clone, Debug)]
+pub struct RekeyCtx<I, K, V, K2>
+where
+    I: ContextIterator<Item = (K, V)>,
+{
+    pub(crate) iter: I,
+    pub(crate) key: fn(&K, &V, &I::Context) -> K2,
+}
+
+impl<I, K, V, K2> Iterator for RekeyCtx<I, K, V, K2>
+where
+    I: ContextIterator<Item = (K, V)>,
+{
+    type Item = (K2, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        Some(((self.key)(&key, &value, self.iter.context()), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, K, V, K2> DoubleEndedIterator for RekeyCtx<I, K, V, K2>
+where
+    I: DoubleEndedIterator + ContextIterator<Item = (K, V)>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next_back()?;
+        Some(((self.key)(&key, &value, self.iter.context()), value))
+    }
+}
+
+impl<I, K, V, K2> ExactSizeIterator for RekeyCtx<I, K, V, K2>
+where
+    I: ExactSizeIterator + ContextIterator<Item = (K, V)>,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, K, V, K2> FusedIterator for RekeyCtx<I, K, V, K2> where
+    I: FusedIterator + ContextIterator<Item = (K, V)>
+{
+}
+
+impl<I, K, V, K2> ContextIterator for RekeyCtx<I, K, V, K2>
+where
+    I: ContextIterator<Item = (K, V)>,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Pair each element of an iterator with its distance to a pivot.
+///
+/// Created by [`ContextIterator::with_distance_to_context`]. The distance is
+/// computed from the element and the context.
+#[derive(Clone, Debug)]
+pub struct DistanceCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) dist: fn(&I::Item, &I::Context) -> f64,
+}
+
+impl<I> Iterator for DistanceCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = (I::Item, f64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let dist = (self.dist)(&item, self.iter.context());
+        Some((item, dist))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for DistanceCtx<I>
+where
+    I: DoubleEndedIterator + ContextIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next_back()?;
+        let dist = (self.dist)(&item, self.iter.context());
+        Some((item, dist))
+    }
+}
+
+impl<I> ExactSizeIterator for DistanceCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for DistanceCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for DistanceCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn map_if() {
+        let iter = (0..6).with_context(3).map_if_with_context(
+            |item: &usize, context: &usize| item < context,
+            |item, context| item * context,
+            |item, _| item,
+        );
+
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.len(), 6);
+        assert!(iter.eq([0, 3, 6, 3, 4, 5]));
+    }
+
+    #[test]
+    fn map_alternating() {
+        let iter = ["width", "3", "height", "4"]
+            .into_iter()
+            .with_context(10)
+            .map_alternating_with_context(
+                |key: &str, _| key.len(),
+                |value, scale: &usize| value.parse::<usize>().unwrap() * scale,
+            );
+
+        assert_eq!(iter.context(), &10);
+        assert_eq!(iter.len(), 4);
+        assert!(iter.eq([5, 30, 6, 40]));
+    }
+
+    #[test]
+    fn rekey() {
+        let iter = [("a", 1), ("b", 2)]
+            .into_iter()
+            .with_context("ns")
+            .rekey_with_context(|key: &&str, _: &usize, prefix: &&str| format!("{prefix}.{key}"));
+
+        assert_eq!(iter.context(), &"ns");
+        assert_eq!(iter.len(), 2);
+        assert!(iter.eq([("ns.a".to_string(), 1), ("ns.b".to_string(), 2)]));
+    }
+
+    #[test]
+    fn with_distance() {
+        let iter = [(0.0, 0.0), (3.0, 4.0), (1.0, 1.0)]
+            .into_iter()
+            .with_context((1.0, 0.0))
+            .with_distance_to_context(|item: &(f64, f64), pivot: &(f64, f64)| {
+                (item.0 - pivot.0).abs() + (item.1 - pivot.1).abs()
+            });
+
+        assert_eq!(iter.context(), &(1.0, 0.0));
+        assert_eq!(iter.len(), 3);
+        assert!(iter
+            .rev()
+            .eq([((1.0, 1.0), 1.0), ((3.0, 4.0), 6.0), ((0.0, 0.0), 1.0)]));
+    }
+}
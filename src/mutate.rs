@@ -0,0 +1,145 @@
+//! Adaptors with a context that evolves during the iteration.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Update the context of an iterator after each element.
+///
+/// Created by [`WithCtx::update_context_each`](crate::WithCtx::update_context_each).
+///
+/// The update for an element is applied at the start of the following call
+/// to `next`, so adaptors further down the pipeline see the context as it was
+/// when the element was yielded. The first element sees the initial context.
+#[derive(Clone, Debug)]
+pub struct UpdateCtx<I, Ctx> {
+    pub(crate) iter: I,
+    pub(crate) context: Ctx,
+    pub(crate) update: fn(&mut Ctx),
+    pub(crate) pending: bool,
+}
+
+impl<I, Ctx> Iterator for UpdateCtx<I, Ctx>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending {
+            (self.update)(&mut self.context);
+        }
+        let item = self.iter.next();
+        self.pending = item.is_some();
+        item
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, Ctx> ExactSizeIterator for UpdateCtx<I, Ctx>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, Ctx> FusedIterator for UpdateCtx<I, Ctx> where I: FusedIterator {}
+
+impl<I, Ctx> ContextIterator for UpdateCtx<I, Ctx>
+where
+    I: Iterator,
+{
+    type Context = Ctx;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+/// An iterator adaptor where the context is the state of a scan.
+///
+/// Created by [`WithCtx::scan_context`](crate::WithCtx::scan_context).
+///
+/// The context is mutated by each call to the scan function, so it is not
+/// constant: adaptors further down the pipeline see the state left by the
+/// element they receive.
+#[derive(Clone, Debug)]
+pub struct ScanContextCtx<I, Ctx, O>
+where
+    I: Iterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: Ctx,
+    pub(crate) f: fn(&mut Ctx, I::Item) -> Option<O>,
+}
+
+impl<I, Ctx, O> Iterator for ScanContextCtx<I, Ctx, O>
+where
+    I: Iterator,
+{
+    type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        (self.f)(&mut self.context, item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, Ctx, O> ContextIterator for ScanContextCtx<I, Ctx, O>
+where
+    I: Iterator,
+{
+    type Context = Ctx;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn update_context_each() {
+        let iter = (0..4)
+            .with_context(10)
+            .update_context_each(|context: &mut usize| *context += 10)
+            .map_with_context(|item: usize, context: &usize| item + context);
+
+        assert_eq!(iter.context(), &10);
+        assert!(iter.eq([10, 21, 32, 43]));
+    }
+
+    #[test]
+    fn scan_context() {
+        let mut iter =
+            [3, 1, 4, 1, 5]
+                .into_iter()
+                .with_context(0)
+                .scan_context(|total: &mut usize, item| {
+                    *total += item;
+                    (*total < 9).then_some(*total)
+                });
+
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.context(), &3);
+        assert!(iter.by_ref().eq([4, 8]));
+        assert_eq!(iter.context(), &9);
+    }
+}
@@ -0,0 +1,113 @@
+//! Contexts returned by value.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Iterator carrying a context that is returned by value.
+///
+/// [`ContextIterator::context`] returns a reference, so any projection of the
+/// context must point to data stored inside it. This trait lifts that
+/// restriction by computing an owned value each time the context is
+/// requested, at the cost of recomputing it on every call.
+///
+/// Every [`ContextIterator`] with a [`Clone`] context implements this trait by
+/// cloning its context. [`CtxMapOwned`] adds a projection to an arbitrary
+/// owned value.
+pub trait OwnedContextIterator: Iterator {
+    /// The owned context type.
+    type ContextOwned;
+
+    /// Compute the context.
+    fn context_owned(&self) -> Self::ContextOwned;
+}
+
+impl<I> OwnedContextIterator for I
+where
+    I: ContextIterator,
+    I::Context: Clone,
+{
+    type ContextOwned = I::Context;
+
+    #[inline]
+    fn context_owned(&self) -> Self::ContextOwned {
+        self.context().clone()
+    }
+}
+
+/// Apply a function to the context of an iterator, producing an owned value.
+///
+/// Created by [`ContextIterator::context_map_owned`]. The function is called
+/// each time the context is requested.
+#[derive(Clone, Debug)]
+pub struct CtxMapOwned<I, F> {
+    pub(crate) iter: I,
+    pub(crate) map: F,
+}
+
+impl<I, F> Iterator for CtxMapOwned<I, F>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, F, O> OwnedContextIterator for CtxMapOwned<I, F>
+where
+    I: ContextIterator,
+    F: Fn(&I::Context) -> O,
+{
+    type ContextOwned = O;
+
+    #[inline]
+    fn context_owned(&self) -> O {
+        (self.map)(self.iter.context())
+    }
+}
+
+impl<I, F> DoubleEndedIterator for CtxMapOwned<I, F>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<I, F> ExactSizeIterator for CtxMapOwned<I, F>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, F> FusedIterator for CtxMapOwned<I, F> where I: FusedIterator {}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn context_owned() {
+        let iter = (0..3).with_context(42);
+        assert_eq!(iter.context_owned(), 42);
+
+        let iter = iter.context_map_owned(|context: &usize| context.to_string());
+        assert_eq!(iter.context_owned(), "42");
+        assert!(iter.eq(0..3));
+    }
+}
@@ -0,0 +1,295 @@
+//! Parallel iterators carrying a context.
+//!
+//! These mirror the synchronous adaptors for [rayon] parallel iterators,
+//! gated behind the `rayon` feature. The context is borrowed for the lifetime
+//! of the iterator and is read-only, so it only needs to be [`Sync`] to be
+//! shared by the worker threads.
+
+use rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+/// Parallel iterator carrying a borrowed context.
+pub trait ContextParallelIterator<'a>: ParallelIterator {
+    /// The context type.
+    type Context: Sync + 'a;
+
+    /// Get the context.
+    fn context(&self) -> &'a Self::Context;
+
+    /// Apply a map to each element in the iterator, in parallel.
+    ///
+    /// The context is shared by reference between the worker threads.
+    fn par_map_with_context<O>(
+        self,
+        map: fn(Self::Item, &Self::Context) -> O,
+    ) -> MapCtxPar<'a, Self, O>
+    where
+        Self: Sized,
+        O: Send,
+    {
+        MapCtxPar {
+            context: self.context(),
+            iter: self,
+            map,
+        }
+    }
+
+    /// Apply a filter over the elements of the iterator, in parallel.
+    ///
+    /// The context is shared by reference between the worker threads.
+    fn par_filter_with_context(
+        self,
+        filter: fn(&Self::Item, &Self::Context) -> bool,
+    ) -> FilterCtxPar<'a, Self>
+    where
+        Self: Sized,
+    {
+        FilterCtxPar {
+            context: self.context(),
+            iter: self,
+            predicate: filter,
+        }
+    }
+}
+
+/// Extended parallel iterator trait to add context data.
+pub trait IntoContextParallelIterator: ParallelIterator {
+    /// Add a read-only context to the parallel iterator.
+    ///
+    /// The context is borrowed, so it does not need to be [`Clone`] or
+    /// [`Send`].
+    fn with_context<Ctx>(self, context: &Ctx) -> WithCtxPar<'_, Self, Ctx>
+    where
+        Self: Sized,
+        Ctx: Sync,
+    {
+        WithCtxPar {
+            iter: self,
+            context,
+        }
+    }
+}
+
+impl<P> IntoContextParallelIterator for P where P: ParallelIterator {}
+
+/// Wrapper around a parallel iterator adding context data.
+#[derive(Clone, Debug)]
+pub struct WithCtxPar<'a, P, Ctx> {
+    pub(self) iter: P,
+    pub(self) context: &'a Ctx,
+}
+
+impl<'a, P, Ctx> ParallelIterator for WithCtxPar<'a, P, Ctx>
+where
+    P: ParallelIterator,
+    Ctx: Sync,
+{
+    type Item = P::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.iter.drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.iter.opt_len()
+    }
+}
+
+impl<'a, P, Ctx> IndexedParallelIterator for WithCtxPar<'a, P, Ctx>
+where
+    P: IndexedParallelIterator,
+    Ctx: Sync,
+{
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        self.iter.drive(consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        self.iter.with_producer(callback)
+    }
+}
+
+impl<'a, P, Ctx> ContextParallelIterator<'a> for WithCtxPar<'a, P, Ctx>
+where
+    P: ParallelIterator,
+    Ctx: Sync,
+{
+    type Context = Ctx;
+
+    fn context(&self) -> &'a Self::Context {
+        self.context
+    }
+}
+
+/// Map a function over each element in a parallel iterator.
+///
+/// Each function call is passed the context of the iterator along with the
+/// element.
+#[derive(Clone, Debug)]
+pub struct MapCtxPar<'a, P, O>
+where
+    P: ContextParallelIterator<'a>,
+{
+    pub(self) iter: P,
+    pub(self) context: &'a P::Context,
+    pub(self) map: fn(P::Item, &P::Context) -> O,
+}
+
+impl<'a, P, O> ParallelIterator for MapCtxPar<'a, P, O>
+where
+    P: ContextParallelIterator<'a>,
+    O: Send,
+{
+    type Item = O;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let (map, context) = (self.map, self.context);
+        self.iter
+            .map(move |item| map(item, context))
+            .drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.iter.opt_len()
+    }
+}
+
+impl<'a, P, O> IndexedParallelIterator for MapCtxPar<'a, P, O>
+where
+    P: ContextParallelIterator<'a> + IndexedParallelIterator,
+    O: Send,
+{
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        let (map, context) = (self.map, self.context);
+        self.iter
+            .map(move |item| map(item, context))
+            .drive(consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let (map, context) = (self.map, self.context);
+        self.iter
+            .map(move |item| map(item, context))
+            .with_producer(callback)
+    }
+}
+
+impl<'a, P, O> ContextParallelIterator<'a> for MapCtxPar<'a, P, O>
+where
+    P: ContextParallelIterator<'a>,
+    O: Send,
+{
+    type Context = P::Context;
+
+    fn context(&self) -> &'a Self::Context {
+        self.context
+    }
+}
+
+/// Filter the elements of a parallel iterator.
+///
+/// Each predicate call is passed the context of the iterator along with the
+/// element.
+#[derive(Clone, Debug)]
+pub struct FilterCtxPar<'a, P>
+where
+    P: ContextParallelIterator<'a>,
+{
+    pub(self) iter: P,
+    pub(self) context: &'a P::Context,
+    pub(self) predicate: fn(&P::Item, &P::Context) -> bool,
+}
+
+impl<'a, P> ParallelIterator for FilterCtxPar<'a, P>
+where
+    P: ContextParallelIterator<'a>,
+{
+    type Item = P::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let (predicate, context) = (self.predicate, self.context);
+        self.iter
+            .filter(move |item| predicate(item, context))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<'a, P> ContextParallelIterator<'a> for FilterCtxPar<'a, P>
+where
+    P: ContextParallelIterator<'a>,
+{
+    type Context = P::Context;
+
+    fn context(&self) -> &'a Self::Context {
+        self.context
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rayon::iter::IntoParallelIterator;
+
+    #[test]
+    fn par_map_and_filter() {
+        let iter = (0..100usize)
+            .into_par_iter()
+            .with_context(&3)
+            .par_filter_with_context(|item: &usize, context: &usize| item % context == 0)
+            .par_map_with_context(|item: usize, context: &usize| item / context);
+
+        assert_eq!(iter.context(), &3);
+        let items: Vec<usize> = iter.collect();
+        assert_eq!(items, (0..34).collect::<Vec<_>>());
+
+        let iter = (0..10usize)
+            .into_par_iter()
+            .with_context(&2)
+            .par_map_with_context(|item: usize, context: &usize| item * context);
+        assert_eq!(iter.len(), 10);
+    }
+
+    #[test]
+    fn par_map_borrowed_context() {
+        // Neither `Clone` nor `Copy`; the workers only share a reference.
+        struct Table(Vec<usize>);
+
+        let table = Table((0..10).map(|i| i * i).collect());
+        let squares: Vec<usize> = (0..10usize)
+            .into_par_iter()
+            .with_context(&table)
+            .par_map_with_context(|item: usize, table: &Table| table.0[item])
+            .collect();
+        assert_eq!(squares, table.0);
+    }
+}
@@ -0,0 +1,597 @@
+//! Adaptors that track the position of the elements in the iterator.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Pair each element with the number of elements remaining after it.
+///
+/// Created by [`ContextIterator::yield_with_remaining`].
+#[derive(Clone, Debug)]
+pub struct WithRemainingCtx<I> {
+    pub(crate) iter: I,
+}
+
+impl<I> Iterator for WithRemainingCtx<I>
+where
+    I: ExactSizeIterator,
+{
+    type Item = (I::Item, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some((item, self.iter.len()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+}
+
+impl<I> ExactSizeIterator for WithRemainingCtx<I>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for WithRemainingCtx<I> where I: FusedIterator + ExactSizeIterator {}
+
+impl<I> ContextIterator for WithRemainingCtx<I>
+where
+    I: ContextIterator + ExactSizeIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Pair each element with its index and a clone of the context.
+///
+/// Created by [`ContextIterator::enumerate_with_context_pairs`].
+#[derive(Clone, Debug)]
+pub struct EnumeratePairsCtx<I> {
+    pub(crate) iter: I,
+    pub(crate) index: usize,
+}
+
+impl<I> EnumeratePairsCtx<I> {
+    /// Set the index back to zero, so the next element read gets index `0`.
+    ///
+    /// Cloning the adaptor copies the current index, so this is useful to
+    /// reuse a cloned pipeline for a fresh batch.
+    #[inline]
+    pub fn reset_index(&mut self) {
+        self.index = 0;
+    }
+}
+
+impl<I> Iterator for EnumeratePairsCtx<I>
+where
+    I: ContextIterator,
+    I::Context: Clone,
+{
+    type Item = (usize, I::Item, I::Context);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some((index, item, self.iter.context().clone()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for EnumeratePairsCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Context: Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for EnumeratePairsCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Context: Clone,
+{
+}
+
+impl<I> ContextIterator for EnumeratePairsCtx<I>
+where
+    I: ContextIterator,
+    I::Context: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Filter the elements of an iterator using their position.
+///
+/// Created by [`ContextIterator::filter_indexed_with_context`]. The index
+/// counts every element of the inner iterator, including the discarded ones.
+#[derive(Clone, Debug)]
+pub struct FilterIndexedCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) predicate: fn(usize, &I::Item, &I::Context) -> bool,
+    pub(crate) index: usize,
+}
+
+impl<I> FilterIndexedCtx<I>
+where
+    I: ContextIterator,
+{
+    /// Set the index back to zero, so the next element read gets index `0`.
+    ///
+    /// Cloning the adaptor copies the current index, so this is useful to
+    /// reuse a cloned pipeline for a fresh batch.
+    #[inline]
+    pub fn reset_index(&mut self) {
+        self.index = 0;
+    }
+}
+
+impl<I> Iterator for FilterIndexedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let index = self.index;
+            self.index += 1;
+            if (self.predicate)(index, &item, self.iter.context()) {
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I> FusedIterator for FilterIndexedCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for FilterIndexedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Add a counter of the yielded elements to the context of an iterator.
+///
+/// Created by [`ContextIterator::numbered`]. The context is a tuple with the
+/// counter and a clone of the original context, taken when the adaptor is
+/// created.
+///
+/// The counter starts at zero and is incremented before each element is
+/// yielded, so adaptors further down the pipeline see `1` with the first
+/// element, `2` with the second, and so on.
+#[derive(Clone, Debug)]
+pub struct NumberedCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: (usize, I::Context),
+}
+
+impl<I> NumberedCtx<I>
+where
+    I: ContextIterator,
+{
+    /// Set the counter back to zero, as if no element had been yielded.
+    ///
+    /// Cloning the adaptor copies the current counter, so this is useful to
+    /// reuse a cloned pipeline for a fresh batch.
+    #[inline]
+    pub fn reset_index(&mut self) {
+        self.context.0 = 0;
+    }
+}
+
+impl<I> Iterator for NumberedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.context.0 += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for NumberedCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for NumberedCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for NumberedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = (usize, I::Context);
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+/// Drop every n-th element of an iterator, with `n` read from the context.
+///
+/// Created by [`ContextIterator::skip_every_nth_with_context`]. Positions are
+/// counted from one, so with `n = 3` the third, sixth, ninth, ... elements are
+/// dropped.
+#[derive(Clone, Debug)]
+pub struct SkipEveryNthCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) n: fn(&I::Context) -> usize,
+    pub(crate) count: usize,
+}
+
+impl<I> Iterator for SkipEveryNthCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let n = (self.n)(self.iter.context());
+            assert!(n != 0, "skip_every_nth_with_context: n must be non-zero");
+            self.count += 1;
+            if self.count >= n {
+                self.count = 0;
+                continue;
+            }
+            return Some(item);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I> FusedIterator for SkipEveryNthCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for SkipEveryNthCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield every `step`-th element of an iterator, adding the step to the
+/// context.
+///
+/// Created by [`ContextIterator::step_by_tracked`]. The context is a tuple
+/// with a clone of the original context, taken when the adaptor is created,
+/// and the step size. The first element is always yielded.
+#[derive(Clone, Debug)]
+pub struct StepByTrackedCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: (I::Context, usize),
+    pub(crate) first_take: bool,
+}
+
+impl<I> StepByTrackedCtx<I>
+where
+    I: ContextIterator,
+{
+    /// Number of elements yielded from `n` remaining inner elements.
+    #[inline]
+    fn steps(&self, n: usize) -> usize {
+        let step = self.context.1;
+        match self.first_take {
+            true if n == 0 => 0,
+            true => 1 + (n - 1) / step,
+            false => n / step,
+        }
+    }
+}
+
+impl<I> Iterator for StepByTrackedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.first_take {
+            self.first_take = false;
+            self.iter.next()
+        } else {
+            self.iter.nth(self.context.1 - 1)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (self.steps(lower), upper.map(|upper| self.steps(upper)))
+    }
+}
+
+impl<I> ExactSizeIterator for StepByTrackedCtx<I> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I> FusedIterator for StepByTrackedCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for StepByTrackedCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = (I::Context, usize);
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+/// Add the upcoming element of an iterator to its context.
+///
+/// Created by [`ContextIterator::with_lookahead`]. The context is a tuple with
+/// a clone of the original context, taken when the adaptor is created, and
+/// the element that the next call to `next` will yield, or `None` at the end.
+///
+/// The first element is read from the inner iterator when the adaptor is
+/// created, and each call to `next` reads one element ahead.
+#[derive(Clone, Debug)]
+pub struct LookaheadCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) context: (I::Context, Option<I::Item>),
+}
+
+impl<I> Iterator for LookaheadCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.context.1.take()?;
+        self.context.1 = self.iter.next();
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.context.1.is_none() {
+            return (0, Some(0));
+        }
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(1),
+            upper.and_then(|upper| upper.checked_add(1)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for LookaheadCtx<I> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I> FusedIterator for LookaheadCtx<I> where I: ContextIterator {}
+
+impl<I> ContextIterator for LookaheadCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = (I::Context, Option<I::Item>);
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn with_remaining() {
+        let iter = (0..3).with_context(42).yield_with_remaining();
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.len(), 3);
+        assert!(iter.eq([(0, 2), (1, 1), (2, 0)]));
+    }
+
+    #[test]
+    fn enumerate_pairs() {
+        let iter = ['a', 'b']
+            .into_iter()
+            .with_context(42)
+            .enumerate_with_context_pairs();
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.len(), 2);
+        assert!(iter.eq([(0, 'a', 42), (1, 'b', 42)]));
+    }
+
+    #[test]
+    fn filter_indexed() {
+        let iter = (10..20).with_context(3).filter_indexed_with_context(
+            |index, _item: &usize, context: &usize| index % context == 0,
+        );
+
+        assert_eq!(iter.context(), &3);
+        assert!(iter.eq([10, 13, 16, 19]));
+    }
+
+    #[test]
+    fn numbered() {
+        let mut iter = (10..13).with_context(42).numbered();
+        assert_eq!(iter.context(), &(0, 42));
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.context(), &(1, 42));
+
+        let iter = iter.map_with_context(|item: usize, (count, _): &(usize, usize)| item * count);
+        assert!(iter.eq([22, 36]));
+    }
+
+    #[test]
+    fn reset_index() {
+        let mut iter = (10..13).with_context(42).numbered();
+        iter.next();
+        let mut fresh = iter.clone();
+        assert_eq!(fresh.context(), &(1, 42));
+        fresh.reset_index();
+        assert_eq!(fresh.context(), &(0, 42));
+        fresh.next();
+        assert_eq!(fresh.context(), &(1, 42));
+        assert_eq!(iter.context(), &(1, 42));
+
+        let mut iter = (0..6)
+            .with_context(2)
+            .filter_indexed_with_context(|index, _: &usize, context: &usize| index % context == 0);
+        assert_eq!(iter.next(), Some(0));
+        iter.reset_index();
+        assert!(iter.eq([1, 3, 5]));
+    }
+
+    #[test]
+    fn skip_every_nth() {
+        let iter = (1..10)
+            .with_context(3)
+            .skip_every_nth_with_context(|context: &usize| *context);
+
+        assert_eq!(iter.context(), &3);
+        assert!(iter.eq([1, 2, 4, 5, 7, 8]));
+    }
+
+    #[test]
+    fn step_by_tracked() {
+        let iter = (0..10)
+            .with_context(1)
+            .step_by_tracked(3)
+            .map_with_context(|item: usize, (scale, step): &(usize, usize)| item * scale / step);
+
+        assert_eq!(iter.context(), &(1, 3));
+        assert_eq!(iter.len(), 4);
+        assert!(iter.eq([0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn lookahead() {
+        let iter = [1, 5, 2, 8].into_iter().with_context(2).with_lookahead();
+        assert_eq!(iter.context(), &(2, Some(1)));
+        assert_eq!(iter.len(), 4);
+
+        let iter = iter.map_with_context(|item: u32, (gap, next): &(u32, Option<u32>)| {
+            next.is_some_and(|next| next >= item + gap)
+        });
+        assert!(iter.eq([true, false, true, false]));
+    }
+}
@@ -0,0 +1,183 @@
+//! Partial projections of the context.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Apply a function to the context of an iterator that may fail to project
+/// it.
+///
+/// Created by [`ContextIterator::context_map_opt`].
+///
+/// [`ContextIterator::context`] must always return a reference, so this
+/// adaptor does not implement [`ContextIterator`]. The projected context is
+/// available through [`CtxMapOpt::context_opt`] instead.
+#[derive(Clone, Debug)]
+pub struct CtxMapOpt<I, F> {
+    pub(crate) iter: I,
+    pub(crate) map: F,
+}
+
+impl<I, F, O> CtxMapOpt<I, F>
+where
+    I: ContextIterator,
+    F: Fn(&I::Context) -> Option<&O>,
+{
+    /// Get the projected context, if present.
+    pub fn context_opt(&self) -> Option<&O> {
+        (self.map)(self.iter.context())
+    }
+}
+
+impl<I, F> Iterator for CtxMapOpt<I, F>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn count(self) -> usize {
+        self.iter.count()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, F> DoubleEndedIterator for CtxMapOpt<I, F>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<I, F> ExactSizeIterator for CtxMapOpt<I, F>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, F> FusedIterator for CtxMapOpt<I, F> where I: FusedIterator {}
+
+/// Pair each element of an iterator with a clone of a projection of its
+/// context.
+///
+/// Created by [`ContextIterator::items_with_projected_context`].
+#[derive(Clone, Debug)]
+pub struct WithProjectedCtx<I, O>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) project: fn(&I::Context) -> &O,
+}
+
+impl<I, O> Iterator for WithProjectedCtx<I, O>
+where
+    I: ContextIterator,
+    O: Clone,
+{
+    type Item = (I::Item, O);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some((item, (self.project)(self.iter.context()).clone()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, O> DoubleEndedIterator for WithProjectedCtx<I, O>
+where
+    I: DoubleEndedIterator + ContextIterator,
+    O: Clone,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next_back()?;
+        Some((item, (self.project)(self.iter.context()).clone()))
+    }
+}
+
+impl<I, O> ExactSizeIterator for WithProjectedCtx<I, O>
+where
+    I: ExactSizeIterator + ContextIterator,
+    O: Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, O> FusedIterator for WithProjectedCtx<I, O>
+where
+    I: FusedIterator + ContextIterator,
+    O: Clone,
+{
+}
+
+impl<I, O> ContextIterator for WithProjectedCtx<I, O>
+where
+    I: ContextIterator,
+    O: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn context_map_opt() {
+        let iter = (0..3)
+            .with_context((Some(1), None::<usize>))
+            .context_map_opt(|context: &(Option<usize>, Option<usize>)| context.0.as_ref());
+        assert_eq!(iter.context_opt(), Some(&1));
+        assert!(iter.eq(0..3));
+
+        let iter = (0..3)
+            .with_context((Some(1), None::<usize>))
+            .context_map_opt(|context: &(Option<usize>, Option<usize>)| context.1.as_ref());
+        assert_eq!(iter.context_opt(), None);
+    }
+
+    #[test]
+    fn items_with_projected_context() {
+        let iter = (0..3)
+            .with_context((42, 'a'))
+            .items_with_projected_context(|context: &(usize, char)| &context.1);
+
+        assert_eq!(iter.context(), &(42, 'a'));
+        assert_eq!(iter.len(), 3);
+        assert!(iter.eq([(0, 'a'), (1, 'a'), (2, 'a')]));
+    }
+}
@@ -0,0 +1,191 @@
+//! Adaptors fixing the length of an iterator.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Pad or truncate an iterator to a length read from the context.
+///
+/// Created by [`ContextIterator::resize_with_context`]. Missing elements are
+/// produced by a fill function that receives the context. Once the inner
+/// iterator returns `None`, it is never polled again.
+#[derive(Clone, Debug)]
+pub struct ResizeCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) remaining: usize,
+    pub(crate) fill: fn(&I::Context) -> I::Item,
+    /// Whether the inner iterator has been exhausted.
+    pub(crate) done: bool,
+}
+
+impl<I> Iterator for ResizeCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        if !self.done {
+            match self.iter.next() {
+                Some(item) => return Some(item),
+                None => self.done = true,
+            }
+        }
+        Some((self.fill)(self.iter.context()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<I> ExactSizeIterator for ResizeCtx<I> where I: ContextIterator {}
+
+impl<I> FusedIterator for ResizeCtx<I> where I: ContextIterator {}
+
+impl<I> ContextIterator for ResizeCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Cap the upper bound of the size hint of an iterator.
+///
+/// Created by [`ContextIterator::clamp_size_hint_with_context`]. The cap is
+/// read from the context on each call to `size_hint`. The lower bound is
+/// capped too, so that the hint stays consistent.
+#[derive(Clone, Debug)]
+pub struct ClampHintCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) max: fn(&I::Context) -> usize,
+}
+
+impl<I> Iterator for ClampHintCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max = (self.max)(self.iter.context());
+        let (lower, upper) = self.iter.size_hint();
+        let upper = upper.map_or(max, |upper| upper.min(max));
+        (lower.min(upper), Some(upper))
+    }
+}
+
+impl<I> FusedIterator for ClampHintCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for ClampHintCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn resize() {
+        let iter = (1..3)
+            .with_context((4, 0))
+            .resize_with_context(|(width, _): &(usize, usize)| *width, |(_, fill)| *fill);
+
+        assert_eq!(iter.context(), &(4, 0));
+        assert_eq!(iter.len(), 4);
+        assert!(iter.eq([1, 2, 0, 0]));
+
+        let iter = (1..10)
+            .with_context((4, 0))
+            .resize_with_context(|(width, _): &(usize, usize)| *width, |(_, fill)| *fill);
+        assert!(iter.eq([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn resize_unfused() {
+        /// Yields `None` on every other call.
+        struct Flaky(u32);
+
+        impl Iterator for Flaky {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<u32> {
+                self.0 += 1;
+                (self.0 % 2 == 0).then_some(self.0)
+            }
+        }
+
+        let iter = Flaky(1)
+            .with_context(0)
+            .resize_with_context(|_| 4, |fill: &u32| *fill);
+        assert!(iter.eq([2, 0, 0, 0]));
+    }
+
+    #[test]
+    fn clamp_size_hint() {
+        let iter = (0..10)
+            .filter(|i| i % 2 == 0)
+            .with_context(3)
+            .clamp_size_hint_with_context(|max: &usize| *max);
+
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.size_hint(), (0, Some(3)));
+        assert!(iter.eq([0, 2, 4, 6, 8]));
+
+        let iter = (0..2)
+            .with_context(3)
+            .clamp_size_hint_with_context(|max: &usize| *max);
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+    }
+}
@@ -0,0 +1,88 @@
+//! Adaptors over iterators of results.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Recover from the errors of an iterator of results.
+///
+/// Created by [`ContextIterator::recover_with_context`]. Each error is passed
+/// to the recovery function along with the context. Errors that can not be
+/// recovered are dropped.
+#[derive(Clone, Debug)]
+pub struct RecoverCtx<I, T, E>
+where
+    I: ContextIterator<Item = Result<T, E>>,
+{
+    pub(crate) iter: I,
+    pub(crate) recover: fn(E, &I::Context) -> Option<T>,
+}
+
+impl<I, T, E> Iterator for RecoverCtx<I, T, E>
+where
+    I: ContextIterator<Item = Result<T, E>>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.iter.next()? {
+                Ok(item) => return Some(item),
+                Err(err) => {
+                    if let Some(item) = (self.recover)(err, self.iter.context()) {
+                        return Some(item);
+                    }
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I, T, E> FusedIterator for RecoverCtx<I, T, E> where
+    I: FusedIterator + ContextIterator<Item = Result<T, E>>
+{
+}
+
+impl<I, T, E> ContextIterator for RecoverCtx<I, T, E>
+where
+    I: ContextIterator<Item = Result<T, E>>,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn recover() {
+        let iter = [Ok(1), Err(-1), Ok(2), Err(-2)]
+            .into_iter()
+            .with_context(0)
+            .recover_with_context(|err: i32, default: &i32| (err == -1).then_some(*default));
+
+        assert_eq!(iter.context(), &0);
+        assert!(iter.eq([1, 0, 2]));
+    }
+}
@@ -0,0 +1,106 @@
+//! Adaptors randomly sampling the elements of an iterator.
+
+use core::iter::FusedIterator;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::ContextIterator;
+
+/// Keep each element of an iterator with a probability read from the context.
+///
+/// Created by [`ContextIterator::sample_with_context`]. The random number
+/// generator is seeded from the context when the adaptor is created, so the
+/// same seed, rates, and elements always keep the same elements. The sequence
+/// of random numbers may change between versions of `rand`.
+#[derive(Clone, Debug)]
+pub struct SampleCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) rate: fn(&I::Context) -> f64,
+    pub(crate) rng: StdRng,
+}
+
+impl<I> SampleCtx<I>
+where
+    I: ContextIterator,
+{
+    /// Sample the elements of `iter`, with a generator seeded by `seed`.
+    pub(crate) fn new(iter: I, rate: fn(&I::Context) -> f64, seed: u64) -> Self {
+        Self {
+            iter,
+            rate,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<I> Iterator for SampleCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if self.rng.gen::<f64>() < (self.rate)(self.iter.context()) {
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I> FusedIterator for SampleCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for SampleCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn sample() {
+        let sample = |seed: u64, rate: f64| {
+            (0..1000)
+                .with_context((seed, rate))
+                .sample_with_context(|(_, rate): &(u64, f64)| *rate, |(seed, _)| *seed)
+        };
+
+        assert_eq!(sample(1, 0.0).count(), 0);
+        assert_eq!(sample(1, 1.0).count(), 1000);
+        assert!(sample(1, 0.5).eq(sample(1, 0.5)));
+        assert!(!sample(1, 0.5).eq(sample(2, 0.5)));
+
+        let kept = sample(1, 0.5).count();
+        assert!((400..600).contains(&kept));
+    }
+}
@@ -0,0 +1,139 @@
+//! Adaptors and helpers selecting elements of an iterator by order.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Restore the heap property after pushing an element at the end.
+///
+/// The heap is ordered so that the root compares as the least element.
+pub(crate) fn sift_up<T>(heap: &mut [T], mut pos: usize, cmp: impl Fn(&T, &T) -> Ordering) {
+    while pos > 0 {
+        let parent = (pos - 1) / 2;
+        if cmp(&heap[pos], &heap[parent]) != Ordering::Less {
+            break;
+        }
+        heap.swap(pos, parent);
+        pos = parent;
+    }
+}
+
+/// Restore the heap property after replacing the root.
+///
+/// The heap is ordered so that the root compares as the least element.
+pub(crate) fn sift_down<T>(heap: &mut [T], mut pos: usize, cmp: impl Fn(&T, &T) -> Ordering) {
+    loop {
+        let mut least = pos;
+        for child in [2 * pos + 1, 2 * pos + 2] {
+            if child < heap.len() && cmp(&heap[child], &heap[least]) == Ordering::Less {
+                least = child;
+            }
+        }
+        if least == pos {
+            break;
+        }
+        heap.swap(pos, least);
+        pos = least;
+    }
+}
+
+/// Sort a nearly sorted iterator with a sliding window.
+///
+/// Created by [`ContextIterator::sort_within_window_with_context`]. The window
+/// is kept as a heap, refilled before each element is yielded.
+#[derive(Clone, Debug)]
+pub struct SortWindowCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) window: usize,
+    pub(crate) cmp: fn(&I::Item, &I::Item, &I::Context) -> Ordering,
+    pub(crate) heap: Vec<I::Item>,
+}
+
+impl<I> Iterator for SortWindowCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let cmp = self.cmp;
+        while self.heap.len() < self.window {
+            let Some(item) = self.iter.next() else {
+                break;
+            };
+            self.heap.push(item);
+            let pos = self.heap.len() - 1;
+            let context = self.iter.context();
+            sift_up(&mut self.heap, pos, |a, b| cmp(a, b, context));
+        }
+        if self.heap.is_empty() {
+            return None;
+        }
+        let item = self.heap.swap_remove(0);
+        let context = self.iter.context();
+        sift_down(&mut self.heap, 0, |a, b| cmp(a, b, context));
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.heap.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for SortWindowCtx<I> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I> FusedIterator for SortWindowCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for SortWindowCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn sort_within_window() {
+        let mut iter = [3, 1, 2, 6, 4, 5, 9]
+            .into_iter()
+            .with_context(10)
+            .sort_within_window_with_context(3, |a: &u32, b, context| {
+                (context - a).cmp(&(context - b)).reverse()
+            });
+
+        assert_eq!(iter.context(), &10);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 6);
+        assert!(iter.eq([2, 3, 4, 5, 6, 9]));
+    }
+}
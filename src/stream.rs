@@ -0,0 +1,233 @@
+//! Asynchronous streams carrying a context.
+//!
+//! These mirror the synchronous adaptors for [`Stream`]s, gated behind the
+//! `async` feature.
+
+use core::pin::Pin;
+use core::task::{Context as TaskContext, Poll};
+
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+/// Stream carrying a context.
+pub trait ContextStream: Stream {
+    /// The context type.
+    type Context;
+
+    /// Get the context.
+    fn context(&self) -> &Self::Context;
+
+    /// Apply a map to each element in the stream.
+    fn map_with_context<O>(self, map: fn(Self::Item, &Self::Context) -> O) -> MapCtxStream<Self, O>
+    where
+        Self: Sized,
+    {
+        MapCtxStream { stream: self, map }
+    }
+
+    /// Apply a filter over the elements of the stream.
+    fn filter_with_context(
+        self,
+        filter: fn(&Self::Item, &Self::Context) -> bool,
+    ) -> FilterCtxStream<Self>
+    where
+        Self: Sized,
+    {
+        FilterCtxStream {
+            stream: self,
+            predicate: filter,
+        }
+    }
+}
+
+/// Extended stream trait to add context data.
+pub trait IntoContextStream: Stream {
+    /// Add read-only context to the stream.
+    fn with_context<Ctx>(self, context: Ctx) -> WithCtxStream<Self, Ctx>
+    where
+        Self: Sized,
+    {
+        WithCtxStream {
+            stream: self,
+            context,
+        }
+    }
+}
+
+impl<S> IntoContextStream for S where S: Stream {}
+
+pin_project! {
+    /// Wrapper around a stream adding context data.
+    #[derive(Clone, Debug)]
+    pub struct WithCtxStream<S, Ctx> {
+        #[pin]
+        stream: S,
+        context: Ctx,
+    }
+}
+
+impl<S, Ctx> Stream for WithCtxStream<S, Ctx>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.project().stream.poll_next(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S, Ctx> ContextStream for WithCtxStream<S, Ctx>
+where
+    S: Stream,
+{
+    type Context = Ctx;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.context
+    }
+}
+
+pin_project! {
+    /// Map a function over each element in the stream.
+    ///
+    /// Each function call is passed the context of the stream along with the
+    /// element.
+    #[derive(Clone, Debug)]
+    pub struct MapCtxStream<S, O>
+    where
+        S: ContextStream,
+    {
+        #[pin]
+        stream: S,
+        map: fn(S::Item, &S::Context) -> O,
+    }
+}
+
+impl<S, O> Stream for MapCtxStream<S, O>
+where
+    S: ContextStream,
+{
+    type Item = O;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        match this.stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(Some((this.map)(item, this.stream.context()))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S, O> ContextStream for MapCtxStream<S, O>
+where
+    S: ContextStream,
+{
+    type Context = S::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.stream.context()
+    }
+}
+
+pin_project! {
+    /// Filter the elements of a stream.
+    ///
+    /// Each predicate call is passed the context of the stream along with the
+    /// element.
+    #[derive(Clone, Debug)]
+    pub struct FilterCtxStream<S>
+    where
+        S: ContextStream,
+    {
+        #[pin]
+        stream: S,
+        predicate: fn(&S::Item, &S::Context) -> bool,
+    }
+}
+
+impl<S> Stream for FilterCtxStream<S>
+where
+    S: ContextStream,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if (this.predicate)(&item, this.stream.context()) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.stream.size_hint().1)
+    }
+}
+
+impl<S> ContextStream for FilterCtxStream<S>
+where
+    S: ContextStream,
+{
+    type Context = S::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.stream.context()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+
+    #[test]
+    fn map_and_filter() {
+        let stream = stream::iter(0..6)
+            .with_context(3)
+            .filter_with_context(|item: &usize, context: &usize| item % context != 0)
+            .map_with_context(|item: usize, context: &usize| item * context);
+
+        assert_eq!(stream.context(), &3);
+        let items: Vec<usize> = block_on(stream.collect());
+        assert_eq!(items, [3, 6, 12, 15]);
+    }
+
+    #[test]
+    fn not_unpin() {
+        let stream = stream::unfold(0, |n| async move { (n < 4).then_some((n, n + 1)) })
+            .with_context(2)
+            .filter_with_context(|item: &usize, context: &usize| item != context)
+            .map_with_context(|item: usize, context: &usize| item + context);
+
+        let items: Vec<usize> = block_on(stream.collect());
+        assert_eq!(items, [2, 3, 5]);
+    }
+}
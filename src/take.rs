@@ -0,0 +1,395 @@
+//! Adaptors that stop the iteration early.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Yield the elements of an iterator only if the context satisfies a
+/// predicate.
+///
+/// Created by [`ContextIterator::gate_on_context`]. The predicate is evaluated
+/// once, on the first call to `next`.
+#[derive(Clone, Debug)]
+pub struct GateCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) predicate: fn(&I::Context) -> bool,
+    pub(crate) open: Option<bool>,
+}
+
+impl<I> Iterator for GateCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let open = *self
+            .open
+            .get_or_insert_with(|| (self.predicate)(self.iter.context()));
+        if open {
+            self.iter.next()
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.open {
+            Some(true) => self.iter.size_hint(),
+            Some(false) => (0, Some(0)),
+            None => (0, self.iter.size_hint().1),
+        }
+    }
+}
+
+impl<I> FusedIterator for GateCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for GateCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield elements of an iterator until its context changes.
+///
+/// Created by [`ContextIterator::take_until_context_change`].
+///
+/// A snapshot of the context is taken on the first call to `next`. Before
+/// pulling each element the current context is compared with the snapshot,
+/// and the iteration stops as soon as they differ. No element is consumed
+/// from the inner iterator once the change is detected, so the rest of it can
+/// be recovered with [`TakeUntilCtxChangeCtx::into_inner`].
+#[derive(Clone, Debug)]
+pub struct TakeUntilCtxChangeCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) snapshot: Option<I::Context>,
+    pub(crate) done: bool,
+}
+
+impl<I> TakeUntilCtxChangeCtx<I>
+where
+    I: ContextIterator,
+{
+    /// Get the inner iterator, to resume the iteration after the context
+    /// changed.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I> Iterator for TakeUntilCtxChangeCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match &self.snapshot {
+            None => self.snapshot = Some(self.iter.context().clone()),
+            Some(snapshot) if snapshot != self.iter.context() => {
+                self.done = true;
+                return None;
+            }
+            Some(_) => {}
+        }
+        let item = self.iter.next();
+        self.done = item.is_none();
+        item
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            true => (0, Some(0)),
+            false => (0, self.iter.size_hint().1),
+        }
+    }
+}
+
+impl<I> FusedIterator for TakeUntilCtxChangeCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+}
+
+impl<I> ContextIterator for TakeUntilCtxChangeCtx<I>
+where
+    I: ContextIterator,
+    I::Context: PartialEq + Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield at most a number of elements read from the context.
+///
+/// Created by [`ContextIterator::limit_from_context`]. The limit is read once,
+/// on the first call to `next`.
+#[derive(Clone, Debug)]
+pub struct LimitCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) limit: fn(&I::Context) -> usize,
+    pub(crate) remaining: Option<usize>,
+}
+
+impl<I> LimitCtx<I>
+where
+    I: ContextIterator,
+{
+    /// The number of elements that may still be yielded.
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.remaining
+            .unwrap_or_else(|| (self.limit)(self.iter.context()))
+    }
+}
+
+impl<I> Iterator for LimitCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.remaining();
+        if remaining == 0 {
+            self.remaining = Some(0);
+            return None;
+        }
+        self.remaining = Some(remaining - 1);
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        let (lower, upper) = self.iter.size_hint();
+        let upper = match upper {
+            Some(upper) => upper.min(remaining),
+            None => remaining,
+        };
+        (lower.min(remaining), Some(upper))
+    }
+}
+
+impl<I> ExactSizeIterator for LimitCtx<I> where I: ExactSizeIterator + ContextIterator {}
+
+impl<I> FusedIterator for LimitCtx<I> where I: FusedIterator + ContextIterator {}
+
+impl<I> ContextIterator for LimitCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Yield elements of an iterator up to and including the first sentinel.
+///
+/// Created by [`ContextIterator::take_until_with_context`].
+#[derive(Clone, Debug)]
+pub struct TakeUntilCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) is_sentinel: fn(&I::Item, &I::Context) -> bool,
+    pub(crate) done: bool,
+}
+
+impl<I> Iterator for TakeUntilCtx<I>
+where
+    I: ContextIterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next();
+        self.done = match &item {
+            Some(item) => (self.is_sentinel)(item, self.iter.context()),
+            None => true,
+        };
+        item
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            true => (0, Some(0)),
+            false => (self.iter.size_hint().0.min(1), self.iter.size_hint().1),
+        }
+    }
+}
+
+impl<I> FusedIterator for TakeUntilCtx<I> where I: ContextIterator {}
+
+impl<I> ContextIterator for TakeUntilCtx<I>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn gate() {
+        let iter = (0..3)
+            .with_context(true)
+            .gate_on_context(|enabled: &bool| *enabled);
+        assert!(iter.eq(0..3));
+
+        let iter = (0..3)
+            .with_context(false)
+            .gate_on_context(|enabled: &bool| *enabled);
+        assert_eq!(iter.context(), &false);
+        assert_eq!(iter.count(), 0);
+    }
+
+    #[test]
+    fn limit_from_context() {
+        let iter = (0..10)
+            .with_context(3)
+            .limit_from_context(|limit: &usize| *limit);
+
+        assert_eq!(iter.context(), &3);
+        assert_eq!(iter.len(), 3);
+        assert!(iter.eq(0..3));
+
+        let iter = (0..2)
+            .with_context(3)
+            .limit_from_context(|limit: &usize| *limit);
+        assert_eq!(iter.len(), 2);
+    }
+
+    #[test]
+    fn take_until() {
+        let iter = "key=value;rest"
+            .chars()
+            .with_context(';')
+            .take_until_with_context(|c: &char, delimiter: &char| c == delimiter);
+
+        assert_eq!(iter.context(), &';');
+        assert!(iter.eq("key=value;".chars()));
+
+        let iter = (0..3)
+            .with_context(10)
+            .take_until_with_context(|item: &usize, context: &usize| item == context);
+        assert!(iter.eq(0..3));
+    }
+
+    /// A context iterator whose context advances every two elements.
+    #[derive(Clone, Debug)]
+    struct Phased(core::ops::Range<usize>, usize);
+
+    impl Iterator for Phased {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<usize> {
+            let item = self.0.next()?;
+            self.1 = (item + 1) / 2;
+            Some(item)
+        }
+    }
+
+    impl ContextIterator for Phased {
+        type Context = usize;
+
+        fn context(&self) -> &usize {
+            &self.1
+        }
+    }
+
+    #[test]
+    fn take_until_context_change() {
+        let mut iter = Phased(0..6, 0).take_until_context_change();
+        assert!(iter.by_ref().eq([0, 1]));
+
+        let mut rest = iter.into_inner();
+        assert_eq!(rest.context(), &1);
+        assert_eq!(rest.next(), Some(2));
+    }
+}
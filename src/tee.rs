@@ -0,0 +1,172 @@
+//! Duplicate a context iterator into two independent halves.
+
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// One half of a duplicated context iterator.
+///
+/// Created by [`ContextIterator::tee_ctx`].
+///
+/// The elements yielded by the leading half are buffered until the other
+/// half reads them, so the buffer grows with the distance between the two.
+/// If one half is consumed entirely before the other, every element of the
+/// iterator is kept in memory.
+pub struct TeeCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(self) shared: Rc<TeeShared<I>>,
+    pub(self) id: bool,
+}
+
+/// State shared between the two halves of a [`TeeCtx`].
+struct TeeShared<I>
+where
+    I: ContextIterator,
+{
+    context: I::Context,
+    buffer: RefCell<TeeBuffer<I>>,
+}
+
+/// Elements read by one half but not yet by the other.
+struct TeeBuffer<I>
+where
+    I: ContextIterator,
+{
+    iter: I,
+    backlog: VecDeque<I::Item>,
+    /// The half the backlog is pending for.
+    owner: bool,
+}
+
+/// Split a context iterator into two halves.
+pub(crate) fn new<I>(iter: I) -> (TeeCtx<I>, TeeCtx<I>)
+where
+    I: ContextIterator,
+    I::Context: Clone,
+{
+    let shared = Rc::new(TeeShared {
+        context: iter.context().clone(),
+        buffer: RefCell::new(TeeBuffer {
+            iter,
+            backlog: VecDeque::new(),
+            owner: false,
+        }),
+    });
+    let first = TeeCtx {
+        shared: shared.clone(),
+        id: true,
+    };
+    let second = TeeCtx { shared, id: false };
+    (first, second)
+}
+
+impl<I> fmt::Debug for TeeCtx<I>
+where
+    I: ContextIterator + fmt::Debug,
+    I::Item: fmt::Debug,
+    I::Context: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buffer = self.shared.buffer.borrow();
+        f.debug_struct("TeeCtx")
+            .field("iter", &buffer.iter)
+            .field("context", &self.shared.context)
+            .field("backlog", &buffer.backlog)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<I> Iterator for TeeCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = self.shared.buffer.borrow_mut();
+        if buffer.owner == self.id {
+            if let Some(item) = buffer.backlog.pop_front() {
+                return Some(item);
+            }
+        }
+        let item = buffer.iter.next()?;
+        buffer.backlog.push_back(item.clone());
+        buffer.owner = !self.id;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffer = self.shared.buffer.borrow();
+        let (lower, upper) = buffer.iter.size_hint();
+        if buffer.owner != self.id {
+            return (lower, upper);
+        }
+        let pending = buffer.backlog.len();
+        (
+            lower.saturating_add(pending),
+            upper.and_then(|upper| upper.checked_add(pending)),
+        )
+    }
+}
+
+impl<I> FusedIterator for TeeCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: Clone,
+{
+}
+
+impl<I> ContextIterator for TeeCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Context = I::Context;
+
+    /// Get the context.
+    ///
+    /// This is a snapshot of the context taken when the iterator was split.
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        &self.shared.context
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.shared.buffer.borrow().iter.context_chain_depth()
+    }
+
+    /// The context is a snapshot, so it never changes.
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn tee() {
+        let (mut a, b) = (0..5).with_context(42).tee_ctx();
+
+        assert_eq!(a.context(), &42);
+        assert_eq!(b.context(), &42);
+        assert_eq!(a.next(), Some(0));
+        assert_eq!(a.next(), Some(1));
+        assert_eq!(b.size_hint().1, Some(5));
+        assert!(b.eq(0..5));
+        assert!(a.eq(2..5));
+    }
+}
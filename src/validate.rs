@@ -0,0 +1,204 @@
+//! Adaptors checking invariants of the elements of an iterator.
+
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Check that the elements of an iterator are sorted, panicking otherwise.
+///
+/// Created by [`ContextIterator::assert_sorted_with_context`]. A clone of the
+/// last element is kept to compare it with the next one. The check is always
+/// enabled, not only in debug builds.
+#[derive(Clone, Debug)]
+pub struct AssertSortedCtx<I>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) cmp: fn(&I::Item, &I::Item, &I::Context) -> Ordering,
+    pub(crate) last: Option<I::Item>,
+}
+
+impl<I> Iterator for AssertSortedCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        if let Some(last) = &self.last {
+            assert!(
+                (self.cmp)(last, &item, self.iter.context()) != Ordering::Greater,
+                "assert_sorted_with_context: elements are not sorted"
+            );
+        }
+        self.last = Some(item.clone());
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for AssertSortedCtx<I>
+where
+    I: ExactSizeIterator + ContextIterator,
+    I::Item: Clone,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I> FusedIterator for AssertSortedCtx<I>
+where
+    I: FusedIterator + ContextIterator,
+    I::Item: Clone,
+{
+}
+
+impl<I> ContextIterator for AssertSortedCtx<I>
+where
+    I: ContextIterator,
+    I::Item: Clone,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Validate the elements of an iterator against the context.
+///
+/// Created by [`ContextIterator::validate_items_with_context`]. Each element
+/// is yielded as `Ok` if it passes the check, or replaced by the error
+/// otherwise.
+#[derive(Clone, Debug)]
+pub struct ValidateCtx<I, E>
+where
+    I: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) check: fn(&I::Item, &I::Context) -> Result<(), E>,
+}
+
+impl<I, E> Iterator for ValidateCtx<I, E>
+where
+    I: ContextIterator,
+{
+    type Item = Result<I::Item, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some((self.check)(&item, self.iter.context()).map(|()| item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, E> ExactSizeIterator for ValidateCtx<I, E>
+where
+    I: ExactSizeIterator + ContextIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, E> FusedIterator for ValidateCtx<I, E> where I: FusedIterator + ContextIterator {}
+
+impl<I, E> ContextIterator for ValidateCtx<I, E>
+where
+    I: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::cmp::Ordering;
+
+    use crate::*;
+
+    fn case_insensitive(a: &&str, b: &&str, ignore_case: &bool) -> Ordering {
+        match ignore_case {
+            true => a.to_lowercase().cmp(&b.to_lowercase()),
+            false => a.cmp(b),
+        }
+    }
+
+    #[test]
+    fn assert_sorted() {
+        let iter = ["a", "B", "c"]
+            .into_iter()
+            .with_context(true)
+            .assert_sorted_with_context(case_insensitive);
+
+        assert_eq!(iter.context(), &true);
+        assert!(iter.eq(["a", "B", "c"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "not sorted")]
+    fn assert_sorted_unsorted() {
+        ["a", "B", "c"]
+            .into_iter()
+            .with_context(false)
+            .assert_sorted_with_context(case_insensitive)
+            .for_each(drop);
+    }
+
+    #[test]
+    fn validate_items() {
+        let iter = ["ab", "abcd", "", "abc"]
+            .into_iter()
+            .with_context(1..4)
+            .validate_items_with_context(
+                |item: &&str, range: &core::ops::Range<usize>| match range.contains(&item.len()) {
+                    true => Ok(()),
+                    false => Err(item.len()),
+                },
+            );
+
+        assert_eq!(iter.context(), &(1..4));
+        assert_eq!(iter.len(), 4);
+        assert!(iter.eq([Ok("ab"), Err(4), Err(0), Ok("abc")]));
+    }
+}
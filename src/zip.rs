@@ -0,0 +1,450 @@
+//! Adaptors combining a context iterator with a second iterator.
+
+use core::iter::FusedIterator;
+
+use crate::ContextIterator;
+
+/// Get the next element of an iterator, unless it has been exhausted.
+#[inline]
+fn poll<T>(iter: &mut impl Iterator<Item = T>, done: &mut bool) -> Option<T> {
+    if *done {
+        return None;
+    }
+    let item = iter.next();
+    *done = item.is_none();
+    item
+}
+
+/// Get the size hint of an iterator, unless it has been exhausted.
+#[inline]
+fn hint(iter: &impl Iterator, done: bool) -> (usize, Option<usize>) {
+    match done {
+        true => (0, Some(0)),
+        false => iter.size_hint(),
+    }
+}
+
+/// Zip two iterators together, panicking if they have different lengths.
+///
+/// The context of the first iterator is forwarded.
+#[derive(Clone, Debug)]
+pub struct ZipEqCtx<I, J> {
+    pub(crate) iter: I,
+    pub(crate) other: J,
+}
+
+impl<I, J> Iterator for ZipEqCtx<I, J>
+where
+    I: Iterator,
+    J: Iterator,
+{
+    type Item = (I::Item, J::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.iter.next(), self.other.next()) {
+            (Some(a), Some(b)) => Some((a, b)),
+            (None, None) => None,
+            _ => panic!("zip_eq_with_context: iterators have different lengths"),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.iter.size_hint();
+        let (b_lower, b_upper) = self.other.size_hint();
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (a_lower.min(b_lower), upper)
+    }
+}
+
+impl<I, J> ExactSizeIterator for ZipEqCtx<I, J>
+where
+    I: ExactSizeIterator,
+    J: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<I, J> ContextIterator for ZipEqCtx<I, J>
+where
+    I: ContextIterator,
+    J: Iterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Zip two context iterators together, after checking that their contexts
+/// are compatible.
+///
+/// Created by [`ContextIterator::zip_with_context_check`]. The check runs
+/// once, on the first call to `next`, and panics if it fails. The context of
+/// the first iterator is forwarded.
+#[derive(Clone, Debug)]
+pub struct ZipCheckedCtx<I, J>
+where
+    I: ContextIterator,
+    J: ContextIterator,
+{
+    pub(crate) iter: I,
+    pub(crate) other: J,
+    pub(crate) check: fn(&I::Context, &J::Context) -> bool,
+    pub(crate) checked: bool,
+}
+
+impl<I, J> Iterator for ZipCheckedCtx<I, J>
+where
+    I: ContextIterator,
+    J: ContextIterator,
+{
+    type Item = (I::Item, J::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.checked {
+            assert!(
+                (self.check)(self.iter.context(), self.other.context()),
+                "zip_with_context_check: incompatible contexts"
+            );
+            self.checked = true;
+        }
+        Some((self.iter.next()?, self.other.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.iter.size_hint();
+        let (b_lower, b_upper) = self.other.size_hint();
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (a_lower.min(b_lower), upper)
+    }
+}
+
+impl<I, J> ExactSizeIterator for ZipCheckedCtx<I, J>
+where
+    I: ExactSizeIterator + ContextIterator,
+    J: ExactSizeIterator + ContextIterator,
+{
+}
+
+impl<I, J> FusedIterator for ZipCheckedCtx<I, J>
+where
+    I: FusedIterator + ContextIterator,
+    J: FusedIterator + ContextIterator,
+{
+}
+
+impl<I, J> ContextIterator for ZipCheckedCtx<I, J>
+where
+    I: ContextIterator,
+    J: ContextIterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// Alternate the elements of two iterators.
+///
+/// Once one of the iterators is exhausted, the remaining elements of the other
+/// are yielded. An exhausted iterator is never polled again, even if it is
+/// not fused. The context of the first iterator is forwarded.
+#[derive(Clone, Debug)]
+pub struct InterleaveCtx<I, J> {
+    pub(crate) iter: I,
+    pub(crate) other: J,
+    pub(crate) flag: bool,
+    pub(crate) iter_done: bool,
+    pub(crate) other_done: bool,
+}
+
+impl<I, J> Iterator for InterleaveCtx<I, J>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.flag = !self.flag;
+        if self.flag {
+            poll(&mut self.iter, &mut self.iter_done)
+                .or_else(|| poll(&mut self.other, &mut self.other_done))
+        } else {
+            poll(&mut self.other, &mut self.other_done)
+                .or_else(|| poll(&mut self.iter, &mut self.iter_done))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = hint(&self.iter, self.iter_done);
+        let (b_lower, b_upper) = hint(&self.other, self.other_done);
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (a_lower.saturating_add(b_lower), upper)
+    }
+}
+
+impl<I, J> FusedIterator for InterleaveCtx<I, J>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+{
+}
+
+impl<I, J> ContextIterator for InterleaveCtx<I, J>
+where
+    I: ContextIterator,
+    J: Iterator<Item = I::Item>,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+/// An element of a [`ZipLongestCtx`] iterator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<A, B> {
+    /// Both iterators yielded an element.
+    Both(A, B),
+    /// Only the first iterator yielded an element.
+    Left(A),
+    /// Only the second iterator yielded an element.
+    Right(B),
+}
+
+/// Zip two iterators together, until both of them are exhausted.
+///
+/// Created by [`ContextIterator::zip_longest_with_context`]. Once one of the
+/// iterators is exhausted, the elements of the other are yielded alone. An
+/// exhausted iterator is never polled again, even if it is not fused. The
+/// context of the first iterator is forwarded.
+#[derive(Clone, Debug)]
+pub struct ZipLongestCtx<I, J> {
+    pub(crate) iter: I,
+    pub(crate) other: J,
+    pub(crate) iter_done: bool,
+    pub(crate) other_done: bool,
+}
+
+impl<I, J> Iterator for ZipLongestCtx<I, J>
+where
+    I: Iterator,
+    J: Iterator,
+{
+    type Item = EitherOrBoth<I::Item, J::Item>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = poll(&mut self.iter, &mut self.iter_done);
+        let b = poll(&mut self.other, &mut self.other_done);
+        match (a, b) {
+            (Some(a), Some(b)) => Some(EitherOrBoth::Both(a, b)),
+            (Some(a), None) => Some(EitherOrBoth::Left(a)),
+            (None, Some(b)) => Some(EitherOrBoth::Right(b)),
+            (None, None) => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = hint(&self.iter, self.iter_done);
+        let (b_lower, b_upper) = hint(&self.other, self.other_done);
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+        (a_lower.max(b_lower), upper)
+    }
+}
+
+impl<I, J> ExactSizeIterator for ZipLongestCtx<I, J>
+where
+    I: ExactSizeIterator,
+    J: ExactSizeIterator,
+{
+}
+
+impl<I, J> FusedIterator for ZipLongestCtx<I, J>
+where
+    I: Iterator,
+    J: Iterator,
+{
+}
+
+impl<I, J> ContextIterator for ZipLongestCtx<I, J>
+where
+    I: ContextIterator,
+    J: Iterator,
+{
+    type Context = I::Context;
+
+    #[inline]
+    fn context(&self) -> &Self::Context {
+        self.iter.context()
+    }
+
+    #[inline]
+    fn context_chain_depth(&self) -> usize {
+        self.iter.context_chain_depth()
+    }
+
+    #[inline]
+    fn context_is_constant(&self) -> bool {
+        self.iter.context_is_constant()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    /// Yields `None` on every other call.
+    #[derive(Clone, Debug)]
+    struct Flaky(u32);
+
+    impl Iterator for Flaky {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            self.0 += 1;
+            (self.0 % 2 == 0).then_some(self.0)
+        }
+    }
+
+    #[test]
+    fn zip_eq() {
+        let iter = (0..3).with_context(42).zip_eq_with_context(3..6);
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.len(), 3);
+        assert!(iter.eq([(0, 3), (1, 4), (2, 5)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn zip_eq_mismatch() {
+        (0..3)
+            .with_context(42)
+            .zip_eq_with_context(0..2)
+            .for_each(drop);
+    }
+
+    #[test]
+    fn zip_checked() {
+        let other = (3..5).with_context(10);
+        let iter = (0..3)
+            .with_context(10)
+            .zip_with_context_check(other, |a: &usize, b: &usize| a == b);
+
+        assert_eq!(iter.context(), &10);
+        assert_eq!(iter.len(), 2);
+        assert!(iter.eq([(0, 3), (1, 4)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "incompatible contexts")]
+    fn zip_checked_mismatch() {
+        let other = (3..5).with_context(20);
+        (0..3)
+            .with_context(10)
+            .zip_with_context_check(other, |a: &usize, b: &usize| a == b)
+            .for_each(drop);
+    }
+
+    #[test]
+    fn interleave() {
+        let iter = (0..2).with_context(42).interleave_with_context(10..14);
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+        assert!(iter.eq([0, 10, 1, 11, 12, 13]));
+    }
+
+    #[test]
+    fn zip_longest() {
+        let iter = (0..3)
+            .with_context(42)
+            .zip_longest_with_context("ab".chars());
+
+        assert_eq!(iter.context(), &42);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert!(iter.eq([
+            EitherOrBoth::Both(0, 'a'),
+            EitherOrBoth::Both(1, 'b'),
+            EitherOrBoth::Left(2),
+        ]));
+
+        let iter = (0..1).with_context(42).zip_longest_with_context(5..7);
+        assert!(iter.eq([EitherOrBoth::Both(0, 5), EitherOrBoth::Right(6)]));
+    }
+
+    #[test]
+    fn interleave_unfused() {
+        let iter = (0..3).with_context(42).interleave_with_context(Flaky(1));
+        assert!(iter.eq([0, 2, 1, 2]));
+    }
+
+    #[test]
+    fn zip_longest_unfused() {
+        let iter = (0..3).with_context(42).zip_longest_with_context(Flaky(1));
+        assert!(iter.eq([
+            EitherOrBoth::Both(0, 2),
+            EitherOrBoth::Left(1),
+            EitherOrBoth::Left(2),
+        ]));
+    }
+}